@@ -10,6 +10,8 @@ use crate::commands::boilerplate::BoilerplateArgs;
 use crate::commands::dependencies::DependenciesArgs;
 use crate::commands::print_schema::PrintSchemaArgs;
 use crate::commands::publish::PublishArgs;
+use crate::commands::readme_cli_help::ReadmeCliHelpArgs;
+use crate::commands::release::ReleaseArgs;
 use crate::commands::setup::SetupArgs;
 use crate::commands::vcs::VcsArgs;
 use crate::commands::version::VersionArgs;
@@ -29,6 +31,8 @@ pub(crate) enum RepoCommand {
     Version(VersionArgs),
     /// Publish.
     Publish(PublishArgs),
+    /// Create forge releases.
+    Release(ReleaseArgs),
     /// Set up boilerplate for the repo.
     Boilerplate(BoilerplateArgs),
     /// Set up a repository checkout.
@@ -39,6 +43,8 @@ pub(crate) enum RepoCommand {
     Workspace(WorkspaceArgs),
     /// Operate on dependencies.
     Dependencies(DependenciesArgs),
+    /// Keep `--help` output in README code fences in sync (using `.config/readme-cli-help.json`).
+    ReadmeCliHelp(ReadmeCliHelpArgs),
     /// Print completions for the given shell.
     Completions(CompletionsArgs),
     /// Print the given JSON schema.
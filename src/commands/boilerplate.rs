@@ -1,13 +1,47 @@
-use std::path::PathBuf;
+use std::{
+    borrow::Cow,
+    fs::{exists, read_to_string, write},
+    path::PathBuf,
+};
 
-use clap::{Args, Subcommand, ValueEnum};
+use clap::{builder::PossibleValue, Args, FromArgMatches, Subcommand, ValueEnum};
 use printable_shell_command::PrintableShellCommand;
 
+use crate::commands::setup::setup_inferred;
 use crate::common::{
-    debug::DebugPrintable,
+    action_pins::{lint_workflow_actions, pin_workflow_actions, DEFAULT_ACTIONS_REMOTE_BASE},
+    boilerplate_lockfile::{
+        content_hash, lockfile_key, BoilerplateLockfile, BoilerplateLockfileEntry,
+    },
+    cargo_toml::{add_binstall_metadata, update_cargo_toml_package, PackageFields},
+    ci::{
+        release_binaries_workflow, release_targets_from_workflow, CIPipeline,
+        DEFAULT_RELEASE_TARGETS, RELEASE_BINARIES_WORKFLOW_PATH,
+    },
+    command::command_must_succeed_interactively,
+    config::CONFIG_PATH,
     ecosystem::Ecosystem,
-    package_manager::PackageManager,
-    template_file::{TemplateFile, TemplateFileArgs, TemplateFileCommand},
+    error::{RepoError, RepoResult},
+    forge::VCSForge,
+    gitignore::{write_gitignore, GITIGNORE_PATH},
+    license::{
+        copyright_or_detect, detect_manifest_license, detected_copyright, update_manifest_licenses,
+        Copyright, SpdxLicense, LICENSE_PATH,
+    },
+    license_headers::license_headers,
+    makefile::{add_makefile_targets, MakefileTarget, MAKEFILE_PATH},
+    package_json::{update_package_json, PackageJsonFields},
+    package_manager::{PackageManager, PackageManagerArgs, PACKAGE_JSON_PATH},
+    readme_cli_help::add_discovered_help,
+    structured_merge::{merge_jsonc, MergePolicies},
+    template::{detect_cargo_package_name, TemplateVariableArgs, TemplateVariables},
+    template_bundle::{ConfigPatch, TemplateBundle, TemplateBundleFile},
+    template_file::{
+        discover_extra_templates, text_similarity, ExtraTemplate, TemplateFile, TemplateFileArgs,
+        TemplateFileCommand,
+    },
+    template_pack::{add_template_pack, template_pack_templates},
+    vcs::VcsKind,
 };
 
 #[derive(Args, Debug)]
@@ -18,37 +52,259 @@ pub(crate) struct BoilerplateArgs {
 
 #[derive(Debug, Subcommand)]
 enum BoilerplateCommand {
-    /// Set up a CI template for GitHub or Codeberg and open for editing.
+    /// Set up a CI workflow for GitHub, Codeberg (Woodpecker), GitLab, or Forgejo Actions and open for editing.
     CI(CIArgs),
     /// Set up a CI template for auto-publishing releases from tags pushed to GitHub, at: .github/workflows/publish-github-release.yaml
     AutoPublishGithubRelease(TemplateFileArgs),
     /// Set up linting using Biome
-    Biome(TemplateFileArgs),
+    Biome(TemplateFileArgs<ApplyCommandsArgs>),
     /// Set up `tsconfig.json`
     Tsconfig(TemplateFileArgs<TsconfigArgs>),
-    /// Set up `readme-cli-help.json`
-    ReadmeCliHelp(TemplateFileArgs),
+    /// Set up a workflow that builds release binaries for a matrix of targets, at: .github/workflows/build-release-binaries.yaml
+    ReleaseBinaries(TemplateFileArgs<ReleaseBinariesArgs>),
+    /// Set up a `LICENSE` file, and set the `license` field in `Cargo.toml` and `package.json` to match.
+    License(TemplateFileArgs<LicenseArgs>),
+    /// Check or apply `SPDX-License-Identifier` headers in source files (`.rs`, `.ts`, `.js`, etc.) that are not ignored by the VCS.
+    LicenseHeaders(LicenseHeadersArgs),
+    /// Add or update a managed block in `.gitignore` for the detected ecosystems. Entries outside the block are left untouched.
+    Gitignore(TemplateVariableArgs),
+    /// Fill in packaging fields of an existing `Cargo.toml` in place: missing `description`/`license`/`repository`, `[package.metadata.binstall]`, and optionally a size-optimized `[profile.release]`.
+    CargoPackage(CargoPackageArgs),
+    /// Create `package.json` (or fill in missing fields of an existing one) with `type: module`, an `exports` map, `types`, `files`, `repository`, and a `packageManager` pin
+    PackageJson(PackageJsonArgs),
+    /// Set up `readme-cli-help.json`. With `--discover`, generate its code fences (and the matching `README.md` sections) from a CLI's subcommands.
+    ReadmeCliHelp(TemplateFileArgs<ReadmeCliHelpArgs>),
     /// Set up `bunfig.toml`
     Bunfig(TemplateFileArgs),
     /// Set up `rust-toolchain.toml`
-    RustToolchain(TemplateFileArgs),
+    RustToolchain(TemplateFileArgs<ApplyCommandsArgs>),
+    /// Set up an executable `postVersion` script at `script/postVersion.ts`, and register it in `.config/repo.json`.
+    PostVersionHook(TemplateFileArgs),
+    /// List templates, including overrides and extra templates from `.config/repo-templates/` and `~/.config/repo/templates/`.
+    List,
+    /// Check whether boilerplate files on disk still match the current templates. Exits with code 8 if any differ.
+    Status(TemplateVariableArgs),
+    /// Three-way merge newer templates into the boilerplate files recorded in `.config/repo-boilerplate.json`, leaving conflict markers where needed.
+    Upgrade(TemplateVariableArgs),
+    /// Scaffold a new project from a preset, then run `repo setup`. Existing files are left untouched.
+    Init(InitArgs),
+    /// Manage template packs: git repositories with templates, available as `repo boilerplate <pack>/<template>`.
+    Pack(PackArgs),
+    /// Extra templates from `.config/repo-templates/`, `~/.config/repo/templates/`, and template packs are available by name (see `repo boilerplate list`).
+    #[command(external_subcommand)]
+    Extra(Vec<String>),
 }
 
-#[derive(Debug, Clone, ValueEnum, Default)]
-enum VCSForge {
-    #[default]
-    #[clap(name = "github")]
-    GitHub,
-    Codeberg,
+#[derive(Args, Debug)]
+pub(crate) struct CIArgs {
+    /// Defaults to the forge of the `origin` remote. Use `all` to keep the workflows for all forges in sync.
+    #[clap(long)]
+    forge: Option<CIForge>,
+
+    #[command(subcommand)]
+    command: CICommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum CICommand {
+    #[command(flatten)]
+    TemplateFile(TemplateFileCommand),
+    /// Pin `uses:` actions in workflows to commit SHAs (with a version comment), resolving tags using `git ls-remote`.
+    Pin(CIPinArgs),
+    /// Check that all `uses:` actions in workflows are pinned to commit SHAs that match their version comments. Exits with code 8 if not.
+    Lint(CIWorkflowFilesArgs),
 }
 
 #[derive(Args, Debug)]
-pub(crate) struct CIArgs {
+struct CIWorkflowFilesArgs {
+    /// Defaults to all workflows in `.github/workflows/`, `.forgejo/workflows/`, and `.gitea/workflows/`.
+    files: Vec<PathBuf>,
+
+    /// Base URL for resolving `owner/repo` actions (e.g. a folder of bare repos for testing).
+    #[clap(long, default_value = DEFAULT_ACTIONS_REMOTE_BASE)]
+    remote_base: String,
+}
+
+#[derive(Args, Debug)]
+struct CIPinArgs {
+    /// Pin to the latest semver tag of each action instead of the current version.
+    #[clap(long)]
+    latest: bool,
+
+    #[command(flatten)]
+    workflow_files_args: CIWorkflowFilesArgs,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct PackArgs {
+    #[command(subcommand)]
+    command: PackCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum PackCommand {
+    /// Clone (or fetch) a template pack into the cache, and pin it by commit in `.config/repo.json`. Templates are read from the `templates/` folder of the pack.
+    Add(PackAddArgs),
+}
+
+#[derive(Args, Debug)]
+struct PackAddArgs {
+    /// A git URL or a local path.
+    source: String,
+
+    /// A branch, tag, or commit. Defaults to the default branch.
+    #[clap(long)]
+    rev: Option<String>,
+
+    /// Defaults to the last component of the source, without `.git`.
+    #[clap(long)]
+    name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct InitArgs {
     #[clap(long)]
-    forge: VCSForge,
+    preset: InitPreset,
+
+    /// Initialize a repository before adding files.
+    #[clap(long)]
+    init_vcs: Option<VcsKind>,
+
+    /// Skip running `repo setup` afterwards.
+    #[clap(long)]
+    no_setup: bool,
 
     #[command(flatten)]
-    template_file_args: TemplateFileArgs,
+    template_variable_args: TemplateVariableArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum InitPreset {
+    #[clap(name = "rust-cli")]
+    RustCli,
+    #[clap(name = "ts-lib")]
+    TsLib,
+    #[clap(name = "rust+npm-binary")]
+    RustNpmBinary,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CIForge {
+    All,
+    Forge(VCSForge),
+}
+
+impl ValueEnum for CIForge {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[
+            Self::All,
+            Self::Forge(VCSForge::GitHub),
+            Self::Forge(VCSForge::Codeberg),
+            Self::Forge(VCSForge::GitLab),
+            Self::Forge(VCSForge::Forgejo),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::All => Some(PossibleValue::new("all")),
+            Self::Forge(forge) => forge.to_possible_value(),
+        }
+    }
+}
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ApplyCommandsArgs {
+    /// Add `lint`/`format` targets to the `Makefile` (and scripts to `package.json`) instead of printing them. Existing targets and scripts are left untouched.
+    #[clap(long)]
+    apply: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ReleaseBinariesArgs {
+    /// Comma-separated target triples. Defaults to macOS, Windows, and Linux on `x86_64` and `aarch64`.
+    #[clap(long, value_delimiter = ',')]
+    targets: Vec<String>,
+    /// Also add `[package.metadata.binstall]` to `Cargo.toml`, so that `cargo binstall` can find the binaries.
+    #[clap(long)]
+    binstall_metadata: bool,
+}
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct LicenseArgs {
+    /// Defaults to the `license` field in `Cargo.toml` or `package.json`.
+    #[clap(long)]
+    spdx: Option<SpdxLicense>,
+    /// Defaults to the holder in the existing `LICENSE`, else `git config user.name` or the `jj` user.
+    #[clap(long)]
+    holder: Option<String>,
+    /// Defaults to the year in the existing `LICENSE`, else the current year.
+    #[clap(long)]
+    year: Option<String>,
+}
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ReadmeCliHelpArgs {
+    /// Command that runs the CLI, split on whitespace (e.g. `./target/debug/repo` or `cargo run --quiet --`). Subcommands are discovered from the `Commands:` section of its clap-style `--help` output.
+    #[clap(long)]
+    discover: Option<String>,
+    /// How many levels of subcommands to discover.
+    #[clap(long, default_value_t = 1, requires = "discover")]
+    max_depth: usize,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct LicenseHeadersArgs {
+    #[command(subcommand)]
+    command: LicenseHeadersCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum LicenseHeadersCommand {
+    /// Report files that are missing a license header (or have one for a different license). Exits with code 8 if there are any.
+    Check(LicenseHeadersFilesArgs),
+    /// Insert missing license headers (after a shebang line, if any), and update headers for a different license.
+    Apply(LicenseHeadersFilesArgs),
+}
+
+#[derive(Args, Debug)]
+struct LicenseHeadersFilesArgs {
+    /// Defaults to all supported files that are tracked (or untracked but not ignored) by `git` or `jj`.
+    files: Vec<PathBuf>,
+
+    /// An SPDX expression. Defaults to the `license` field in `Cargo.toml` or `package.json`.
+    #[clap(long)]
+    license: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct PackageJsonArgs {
+    /// Defaults to the `Cargo.toml` package name or the folder name.
+    #[clap(long)]
+    name: Option<String>,
+
+    /// Defaults to the package manager of the lockfile (or `npm`). The `packageManager` pin uses its installed version.
+    #[command(flatten)]
+    package_manager_args: PackageManagerArgs,
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct CargoPackageArgs {
+    /// Defaults to the `description` in `package.json`.
+    #[clap(long)]
+    description: Option<String>,
+    /// An SPDX expression. Defaults to the `license` in `package.json`.
+    #[clap(long)]
+    license: Option<String>,
+    /// Defaults to the web URL of the `origin` remote.
+    #[clap(long)]
+    repository: Option<String>,
+    /// Don't add `[package.metadata.binstall]`.
+    #[clap(long)]
+    no_binstall_metadata: bool,
+    /// Add a `[profile.release]` that optimizes for binary size (unless there is one already).
+    #[clap(long)]
+    size_optimized_release_profile: bool,
 }
 
 #[derive(Args, Clone, Debug)]
@@ -66,22 +322,21 @@ enum ESModule {
     ES2024,
 }
 
-fn ci_template(forge: &VCSForge) -> TemplateFile<'static> {
-    match forge {
-        VCSForge::GitHub => {
-            let bytes = include_bytes!("../templates/.github/workflows/CI.yaml");
-            TemplateFile {
-                relative_path: PathBuf::from("./.github/workflows/CI.yaml"),
-                bytes,
-            }
-        }
-        VCSForge::Codeberg => {
-            let bytes = include_bytes!("../templates/.woodpecker/CI.yaml");
-            TemplateFile {
-                relative_path: PathBuf::from("./.woodpecker/CI.yaml"),
-                bytes,
-            }
-        }
+/// The CI workflow is generated from the ecosystems and package managers (see `CIPipeline`), rather than embedded.
+fn ci_template(forge: &VCSForge, template_variables: &TemplateVariables) -> TemplateFile<'static> {
+    let relative_path = match forge {
+        VCSForge::GitHub => "./.github/workflows/CI.yaml",
+        VCSForge::Codeberg => "./.woodpecker/CI.yaml",
+        VCSForge::GitLab => "./.gitlab-ci.yml",
+        VCSForge::Forgejo => "./.forgejo/workflows/CI.yaml",
+    };
+    TemplateFile {
+        relative_path: PathBuf::from(relative_path),
+        bytes: Cow::Owned(
+            CIPipeline::from_template_variables(template_variables)
+                .render(*forge)
+                .into_bytes(),
+        ),
     }
 }
 
@@ -89,7 +344,26 @@ fn publish_github_release_template() -> TemplateFile<'static> {
     let bytes = include_bytes!("../templates/.github/workflows/publish-github-release.yaml");
     TemplateFile {
         relative_path: PathBuf::from("./.github/workflows/publish-github-release.yaml"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
+    }
+}
+
+/// Uses the targets from the existing workflow (if any) when none are specified, so that `diff` and `status` compare like for like.
+/// Binaries are named after the crate rather than `{{project_name}}` (which prefers the `package.json` name).
+fn release_binaries_template(targets: &[String]) -> TemplateFile<'static> {
+    let binary_name = detect_cargo_package_name().unwrap_or_else(|| "{{project_name}}".to_owned());
+    let targets = if !targets.is_empty() {
+        targets.to_vec()
+    } else {
+        read_to_string(RELEASE_BINARIES_WORKFLOW_PATH)
+            .map(|source| release_targets_from_workflow(&source))
+            .ok()
+            .filter(|targets| !targets.is_empty())
+            .unwrap_or_else(|| DEFAULT_RELEASE_TARGETS.map(str::to_owned).to_vec())
+    };
+    TemplateFile {
+        relative_path: PathBuf::from(RELEASE_BINARIES_WORKFLOW_PATH),
+        bytes: Cow::Owned(release_binaries_workflow(&binary_name, &targets).into_bytes()),
     }
 }
 
@@ -97,47 +371,143 @@ fn biome_json_template() -> TemplateFile<'static> {
     let bytes = include_bytes!("../templates/biome.json");
     TemplateFile {
         relative_path: PathBuf::from("./biome.json"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
     }
 }
 
-fn tsconfig_template(
-    template_file_command: TemplateFileCommand<TsconfigArgs>,
-) -> TemplateFile<'static> {
-    let bytes: &[u8] = {
-        if let TemplateFileCommand::Add(template_file_create_args) = template_file_command {
-            match (
-                template_file_create_args.custom_args.no_dom,
-                template_file_create_args
-                    .custom_args
-                    .module
-                    .unwrap_or_default(),
-            ) {
-                (false, ESModule::ES2022) => include_bytes!("../templates/tsconfig.es2022.json"),
-                (false, ESModule::ES2024) => include_bytes!("../templates/tsconfig.es2024.json"),
-                (true, ESModule::ES2022) => {
-                    include_bytes!("../templates/tsconfig.es2022.no-dom.json")
-                }
-                (true, ESModule::ES2024) => {
-                    include_bytes!("../templates/tsconfig.es2024.no-dom.json")
-                }
-            }
-        } else {
-            // TODO: re-architect the need for this fallback.
-            include_bytes!("../templates/tsconfig.es2022.json")
-        }
+fn tsconfig_template_variant(no_dom: bool, module: ESModule) -> TemplateFile<'static> {
+    let bytes: &[u8] = match (no_dom, module) {
+        (false, ESModule::ES2022) => include_bytes!("../templates/tsconfig.es2022.json"),
+        (false, ESModule::ES2024) => include_bytes!("../templates/tsconfig.es2024.json"),
+        (true, ESModule::ES2022) => include_bytes!("../templates/tsconfig.es2022.no-dom.json"),
+        (true, ESModule::ES2024) => include_bytes!("../templates/tsconfig.es2024.no-dom.json"),
     };
     TemplateFile {
         relative_path: PathBuf::from("./tsconfig.json"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
+    }
+}
+
+fn tsconfig_template_variants() -> Vec<TemplateFile<'static>> {
+    [
+        (false, ESModule::ES2022),
+        (false, ESModule::ES2024),
+        (true, ESModule::ES2022),
+        (true, ESModule::ES2024),
+    ]
+    .into_iter()
+    .map(|(no_dom, module)| tsconfig_template_variant(no_dom, module))
+    .collect()
+}
+
+/// The variant that is closest to the file on disk (or the first variant, if there is no file).
+fn closest_template_variant(
+    variants: Vec<TemplateFile<'static>>,
+    template_variable_args: &TemplateVariableArgs,
+) -> RepoResult<Option<TemplateFile<'static>>> {
+    let template_variables = TemplateVariables::from_args_or_detect(template_variable_args);
+    let mut closest: Option<(f32, TemplateFile<'static>)> = None;
+    for template_file in variants {
+        let Some(current_bytes) = template_file.current_bytes()? else {
+            return Ok(Some(template_file));
+        };
+        let rendered_bytes = template_file.rendered_bytes(&template_variables)?;
+        let ratio = text_similarity(&current_bytes, &rendered_bytes);
+        if closest
+            .as_ref()
+            .is_none_or(|(closest_ratio, _)| ratio > *closest_ratio)
+        {
+            closest = Some((ratio, template_file));
+        }
+    }
+    Ok(closest.map(|(_, template_file)| template_file))
+}
+
+fn tsconfig_template(
+    template_file_command: TemplateFileCommand<TsconfigArgs>,
+) -> RepoResult<TemplateFile<'static>> {
+    match template_file_command {
+        TemplateFileCommand::Add(template_file_create_args) => Ok(tsconfig_template_variant(
+            template_file_create_args.custom_args.no_dom,
+            template_file_create_args
+                .custom_args
+                .module
+                .unwrap_or_default(),
+        )),
+        TemplateFileCommand::Diff(template_variable_args) => Ok(closest_template_variant(
+            tsconfig_template_variants(),
+            &template_variable_args,
+        )?
+        .unwrap_or_else(|| tsconfig_template_variant(false, ESModule::default()))),
+        // `edit` and `reveal` only use the path.
+        _ => Ok(tsconfig_template_variant(false, ESModule::default())),
+    }
+}
+
+fn license_template(license: SpdxLicense, copyright: &Copyright) -> TemplateFile<'static> {
+    TemplateFile {
+        relative_path: PathBuf::from(LICENSE_PATH),
+        bytes: Cow::Owned(license.text(copyright).into_bytes()),
     }
 }
 
+/// A variant for each supported license, using the detected copyright.
+fn license_template_variants() -> RepoResult<Vec<TemplateFile<'static>>> {
+    SpdxLicense::all()
+        .into_iter()
+        .map(|license| Ok(license_template(license, &detected_copyright(license)?)))
+        .collect()
+}
+
+fn add_license(template_file_args: TemplateFileArgs<LicenseArgs>) -> RepoResult<()> {
+    let TemplateFileCommand::Add(template_file_create_args) = &template_file_args.command else {
+        let template_file = match &template_file_args.command {
+            TemplateFileCommand::Diff(template_variable_args) => match detect_manifest_license() {
+                Some(license) => license_template(license, &detected_copyright(license)?),
+                None => closest_template_variant(
+                    license_template_variants()?,
+                    template_variable_args,
+                )?
+                .ok_or_else(|| {
+                    RepoError::detection_failure(
+                        "Could not determine the license. Set `license` in `Cargo.toml` or `package.json`.",
+                    )
+                })?,
+            },
+            // `edit` and `reveal` only use the path.
+            _ => TemplateFile {
+                relative_path: PathBuf::from(LICENSE_PATH),
+                bytes: Cow::Borrowed(b""),
+            },
+        };
+        return template_file.handle_command(template_file_args);
+    };
+    let license_args = template_file_create_args.custom_args.clone();
+    let Some(license) = license_args.spdx.or_else(detect_manifest_license) else {
+        return Err(RepoError::user_error(
+            "Could not determine the license from `Cargo.toml` or `package.json`. Pass `--spdx`.",
+        ));
+    };
+    let copyright = copyright_or_detect(license, license_args.year, license_args.holder)?;
+    license_template(license, &copyright).handle_command(template_file_args)?;
+    update_manifest_licenses(license)
+}
+
+fn boilerplate_gitignore(template_variable_args: TemplateVariableArgs) -> RepoResult<()> {
+    let template_variables = TemplateVariables::from_args_or_detect(&template_variable_args);
+    if write_gitignore(&template_variables.ecosystems)? {
+        eprintln!("Updated: {}", GITIGNORE_PATH);
+    } else {
+        eprintln!("Already up to date: {}", GITIGNORE_PATH);
+    }
+    Ok(())
+}
+
 fn bunfig_template() -> TemplateFile<'static> {
     let bytes = include_bytes!("../templates/bunfig.toml");
     TemplateFile {
         relative_path: PathBuf::from("./bunfig.toml"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
     }
 }
 
@@ -145,7 +515,7 @@ fn readme_cli_help_template() -> TemplateFile<'static> {
     let bytes = include_bytes!("../templates/.config/readme-cli-help.json");
     TemplateFile {
         relative_path: PathBuf::from("./.config/readme-cli-help.json"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
     }
 }
 
@@ -153,64 +523,465 @@ fn rust_toolchain_template() -> TemplateFile<'static> {
     let bytes = include_bytes!("../templates/rust-toolchain.toml");
     TemplateFile {
         relative_path: PathBuf::from("./rust-toolchain.toml"),
-        bytes,
+        bytes: Cow::Borrowed(bytes),
     }
 }
 
-fn add_biome(template_file_args: TemplateFileArgs) {
-    let (binary, args, biome_command_prefix) =
-        match PackageManager::auto_detect_preferred_package_manager_for_ecosystem(
-            Ecosystem::JavaScript,
-        ) {
-            // TODO: generalize to a function to add a dependency
-            Some(PackageManager::Npm) => (
-                "npm",
-                [
-                    "install",
-                    "--save-dev",
-                    "@biomejs/biome",
-                    "@cubing/dev-config",
-                ],
-                "bun x @biomejs/biome",
-            ),
-            Some(PackageManager::Bun) => (
-                "bun",
-                [
-                    "add",
-                    "--development",
-                    "@biomejs/biome",
-                    "@cubing/dev-config",
-                ],
-                "bun x @biomejs/biome",
-            ),
-            Some(PackageManager::Yarn) => (
-                "yarn",
-                ["add", "--dev", "@biomejs/biome", "@cubing/dev-config"],
-                "npx yarn exec @biomejs/biome",
+fn post_version_hook_bundle() -> TemplateBundle<'static> {
+    TemplateBundle {
+        files: vec![TemplateBundleFile {
+            template_file: TemplateFile {
+                relative_path: PathBuf::from("./script/postVersion.ts"),
+                bytes: Cow::Borrowed(include_bytes!("../templates/script/postVersion.ts")),
+            },
+            mode: Some(0o755),
+        }],
+        config_patches: vec![ConfigPatch {
+            relative_path: PathBuf::from(CONFIG_PATH),
+            patch: include_str!("../templates/.config/repo.postVersion.json"),
+        }],
+    }
+}
+
+/// Generated templates (like CI workflows) depend on `template_variables`.
+fn embedded_templates(
+    template_variables: &TemplateVariables,
+) -> Vec<(&'static str, TemplateFile<'static>)> {
+    vec![
+        (
+            "ci --forge github",
+            ci_template(&VCSForge::GitHub, template_variables),
+        ),
+        (
+            "ci --forge codeberg",
+            ci_template(&VCSForge::Codeberg, template_variables),
+        ),
+        (
+            "ci --forge gitlab",
+            ci_template(&VCSForge::GitLab, template_variables),
+        ),
+        (
+            "ci --forge forgejo",
+            ci_template(&VCSForge::Forgejo, template_variables),
+        ),
+        (
+            "auto-publish-github-release",
+            publish_github_release_template(),
+        ),
+        ("release-binaries", release_binaries_template(&[])),
+        ("biome", biome_json_template()),
+        ("bunfig", bunfig_template()),
+        ("readme-cli-help", readme_cli_help_template()),
+        ("rust-toolchain", rust_toolchain_template()),
+    ]
+    .into_iter()
+    .chain(
+        tsconfig_template_variants()
+            .into_iter()
+            .map(|template_file| ("tsconfig", template_file)),
+    )
+    // If the copyright holder can't be detected, `list` and `status` report it instead.
+    .chain(
+        license_template_variants()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|template_file| ("license", template_file)),
+    )
+    .chain(
+        post_version_hook_bundle()
+            .files
+            .into_iter()
+            .map(|bundle_file| ("post-version-hook", bundle_file.template_file)),
+    )
+    .collect()
+}
+
+/// Extra templates from the override folders, followed by those from template packs.
+fn extra_templates(
+    embedded_templates: &[(&'static str, TemplateFile<'static>)],
+) -> RepoResult<Vec<ExtraTemplate>> {
+    let embedded_relative_paths: Vec<PathBuf> = embedded_templates
+        .iter()
+        .map(|(_, template_file)| template_file.relative_path.clone())
+        .collect();
+    let mut extra_templates = discover_extra_templates(&embedded_relative_paths)?;
+    extra_templates.extend(template_pack_templates()?);
+    Ok(extra_templates)
+}
+
+fn list_templates() -> RepoResult<()> {
+    let template_variables =
+        TemplateVariables::from_args_or_detect(&TemplateVariableArgs::default());
+    let embedded_templates = embedded_templates(&template_variables);
+    for (name, template_file) in &embedded_templates {
+        match template_file.override_path() {
+            Some(override_path) => println!(
+                "{} → {} (from: {})",
+                name,
+                template_file.relative_path.to_string_lossy(),
+                override_path.to_string_lossy()
             ),
-            Some(PackageManager::Pnpm) => (
-                "pnpm",
-                [
-                    "install",
-                    "--save-dev",
-                    "@biomejs/biome",
-                    "@cubing/dev-config",
-                ],
-                "npx pnpm exec biome",
+            None => println!(
+                "{} → {}",
+                name,
+                template_file.relative_path.to_string_lossy()
             ),
-            Some(PackageManager::Cargo) => panic!("unrechachable"),
-            None => {
-                panic!("No JS package detected.")
+        }
+    }
+    if let Err(error) = license_template_variants() {
+        println!("license → {} (⚠️ {})", LICENSE_PATH, error);
+    }
+    for extra_template in extra_templates(&embedded_templates)? {
+        println!(
+            "{} → {} (from: {})",
+            extra_template.name,
+            extra_template.template_file.relative_path.to_string_lossy(),
+            extra_template.source_path.to_string_lossy()
+        );
+    }
+    Ok(())
+}
+
+fn all_templates(
+    template_variables: &TemplateVariables,
+) -> RepoResult<Vec<(String, TemplateFile<'static>)>> {
+    let embedded_templates = embedded_templates(template_variables);
+    let extra_templates = extra_templates(&embedded_templates)?;
+    let mut templates: Vec<(String, TemplateFile<'static>)> = embedded_templates
+        .into_iter()
+        .map(|(name, template_file)| (name.to_owned(), template_file))
+        .collect();
+    templates.extend(
+        extra_templates
+            .into_iter()
+            .map(|extra_template| (extra_template.name, extra_template.template_file)),
+    );
+    Ok(templates)
+}
+
+fn boilerplate_status(template_variable_args: TemplateVariableArgs) -> RepoResult<()> {
+    let template_variables = TemplateVariables::from_args_or_detect(&template_variable_args);
+    let templates = all_templates(&template_variables)?;
+
+    // Some paths (e.g. `tsconfig.json`) have multiple template variants. A file matches if it matches any of them.
+    let mut checked_paths: Vec<PathBuf> = vec![];
+    let mut num_present = 0;
+    let mut num_differing = 0;
+    for (name, template_file) in &templates {
+        if checked_paths.contains(&template_file.relative_path) {
+            continue;
+        }
+        checked_paths.push(template_file.relative_path.clone());
+        let Some(current_bytes) = template_file.current_bytes()? else {
+            continue;
+        };
+        num_present += 1;
+        let mut matches = false;
+        for (_, variant) in templates
+            .iter()
+            .filter(|(_, variant)| variant.relative_path == template_file.relative_path)
+        {
+            if variant.rendered_bytes(&template_variables)? == current_bytes {
+                matches = true;
+                break;
+            }
+        }
+        let path = template_file.relative_path.to_string_lossy();
+        if matches {
+            println!("✅ {}", path);
+        } else {
+            println!("❌ {} (see: `repo boilerplate {} diff`)", path, name);
+            num_differing += 1;
+        }
+    }
+    if let Err(error) = license_template_variants() {
+        if exists(LICENSE_PATH)? {
+            num_present += 1;
+            println!("⚠️ {} ({})", LICENSE_PATH, error);
+        }
+    }
+    if num_present == 0 {
+        eprintln!("No boilerplate files found.");
+    }
+    if num_differing > 0 {
+        return Err(RepoError::check_failed(format!(
+            "{} boilerplate file(s) differ from the current templates.",
+            num_differing
+        )));
+    }
+    Ok(())
+}
+
+fn boilerplate_upgrade(template_variable_args: TemplateVariableArgs) -> RepoResult<()> {
+    let mut lockfile = BoilerplateLockfile::read()?;
+    if lockfile.files.is_empty() {
+        eprintln!("No boilerplate files are recorded in `.config/repo-boilerplate.json` (files are recorded when they are added).");
+        return Ok(());
+    }
+    // Extra templates (e.g. from template packs) don't depend on the variables, so they are only read once.
+    let extra_templates = extra_templates(&embedded_templates(
+        &TemplateVariables::from_args_or_detect(&template_variable_args),
+    ))?;
+
+    let mut num_conflicted = 0;
+    for (key, entry) in lockfile.files.clone() {
+        let template_variables = TemplateVariables::from_args_or_detect(
+            &template_variable_args.clone().or(entry.variables.clone()),
+        );
+        // Generated templates depend on the variables recorded for each file.
+        let embedded_templates = embedded_templates(&template_variables);
+        // Some paths (e.g. `tsconfig.json`) have multiple template variants. Prefer the unchanged one, else the one closest to the recorded base.
+        let mut candidates: Vec<(&TemplateFile<'static>, Vec<u8>)> = vec![];
+        for template_file in embedded_templates
+            .iter()
+            .map(|(_, template_file)| template_file)
+            .chain(
+                extra_templates
+                    .iter()
+                    .map(|extra_template| &extra_template.template_file),
+            )
+        {
+            if lockfile_key(&template_file.relative_path) == key {
+                candidates.push((
+                    template_file,
+                    template_file.rendered_bytes(&template_variables)?,
+                ));
+            }
+        }
+        let Some((template_file, rendered_bytes)) = candidates
+            .iter()
+            .find(|(_, rendered_bytes)| content_hash(rendered_bytes) == entry.hash)
+            .or_else(|| {
+                candidates.iter().max_by(|(_, a), (_, b)| {
+                    text_similarity(a, entry.base.as_bytes())
+                        .total_cmp(&text_similarity(b, entry.base.as_bytes()))
+                })
+            })
+        else {
+            eprintln!("⚠️ No template found for: {}", key);
+            continue;
+        };
+        let path = template_file.relative_path.to_string_lossy();
+
+        if content_hash(rendered_bytes) == entry.hash {
+            println!("✅ {} (up to date)", path);
+            continue;
+        }
+        let Some(current_bytes) = template_file.current_bytes()? else {
+            eprintln!("⚠️ Skipping file that no longer exists: {}", path);
+            continue;
+        };
+        let Ok(rendered) = String::from_utf8(rendered_bytes.clone()) else {
+            eprintln!("⚠️ Skipping binary template: {}", path);
+            continue;
+        };
+        let merged = match diffy::merge(
+            &entry.base,
+            &String::from_utf8_lossy(&current_bytes),
+            &rendered,
+        ) {
+            Ok(merged) => {
+                println!("⬆️ {} (upgraded)", path);
+                merged
+            }
+            Err(conflicted) => {
+                println!("⚠️ {} (upgraded with conflicts)", path);
+                num_conflicted += 1;
+                conflicted
             }
         };
-    PrintableShellCommand::new(binary)
-        .arg_each(args)
-        .debug_print()
-        .spawn()
-        .expect("Could not add development dependency")
-        .wait()
-        .unwrap();
-    biome_json_template().handle_command(template_file_args);
+        write(&template_file.relative_path, merged)?;
+        lockfile.files.insert(
+            key,
+            BoilerplateLockfileEntry {
+                hash: content_hash(rendered.as_bytes()),
+                base: rendered,
+                variables: entry.variables,
+            },
+        );
+    }
+    lockfile.write()?;
+
+    if num_conflicted > 0 {
+        return Err(RepoError::check_failed(format!(
+            "{} boilerplate file(s) have merge conflicts. Resolve the conflict markers and commit the result.",
+            num_conflicted
+        )));
+    }
+    Ok(())
+}
+
+fn extra_template(args: Vec<String>) -> RepoResult<()> {
+    let Some(name) = args.first() else {
+        return Err(RepoError::user_error("No template name specified."));
+    };
+    let template_variables =
+        TemplateVariables::from_args_or_detect(&TemplateVariableArgs::default());
+    let extra_templates = extra_templates(&embedded_templates(&template_variables))?;
+    let mut matching = extra_templates
+        .into_iter()
+        .filter(|extra_template| &extra_template.name == name);
+    let Some(extra_template) = matching.next() else {
+        return Err(RepoError::user_error(format!(
+            "Unknown template: {} (see `repo boilerplate list`)",
+            name
+        )));
+    };
+    if let Some(other) = matching.next() {
+        return Err(RepoError::user_error(format!(
+            "Ambiguous template name `{}`: {} and {}",
+            name,
+            extra_template.source_path.to_string_lossy(),
+            other.source_path.to_string_lossy()
+        )));
+    }
+
+    let command = <TemplateFileArgs as Args>::augment_args(
+        clap::Command::new("repo boilerplate").bin_name(format!("repo boilerplate {}", name)),
+    );
+    let arg_matches = command.get_matches_from(args);
+    let template_file_args = match <TemplateFileArgs>::from_arg_matches(&arg_matches) {
+        Ok(template_file_args) => template_file_args,
+        Err(error) => error.exit(),
+    };
+    extra_template
+        .template_file
+        .handle_command(template_file_args)
+}
+
+fn apply_makefile_targets(targets: &[MakefileTarget]) -> RepoResult<()> {
+    let source = if exists(MAKEFILE_PATH)? {
+        read_to_string(MAKEFILE_PATH)?
+    } else {
+        String::new()
+    };
+    let (contents, added) = add_makefile_targets(&source, targets);
+    if added.is_empty() {
+        eprintln!("`Makefile` already has the targets.");
+        return Ok(());
+    }
+    write(MAKEFILE_PATH, contents)?;
+    eprintln!("Added `Makefile` targets: {}", added.join(", "));
+    Ok(())
+}
+
+/// Adds scripts to `package.json`, leaving any existing scripts with the same names untouched.
+fn apply_package_json_scripts(scripts: &[(&str, String)]) -> RepoResult<()> {
+    let source = read_to_string(PACKAGE_JSON_PATH)?;
+    let template = serde_json::json!({
+        "scripts": serde_json::Map::from_iter(
+            scripts
+                .iter()
+                .map(|(name, script)| (name.to_string(), serde_json::Value::from(script.clone())))
+        )
+    });
+    let Ok(template) = serde_json::to_string_pretty(&template) else {
+        return Err(RepoError::internal(
+            "Could not serialize `package.json` scripts.",
+        ));
+    };
+    let contents = merge_jsonc(&source, &template, MergePolicies::default())?;
+    if contents == source {
+        eprintln!("`package.json` already has the scripts.");
+        return Ok(());
+    }
+    write(PACKAGE_JSON_PATH, contents)?;
+    eprintln!("Added `package.json` scripts.");
+    Ok(())
+}
+
+fn add_release_binaries(
+    template_file_args: TemplateFileArgs<ReleaseBinariesArgs>,
+) -> RepoResult<()> {
+    let TemplateFileCommand::Add(template_file_create_args) = &template_file_args.command else {
+        return release_binaries_template(&[]).handle_command(template_file_args);
+    };
+    let custom_args = template_file_create_args.custom_args.clone();
+    release_binaries_template(&custom_args.targets).handle_command(template_file_args)?;
+    if custom_args.binstall_metadata {
+        add_binstall_metadata()?;
+    }
+    Ok(())
+}
+
+/// The command to install Biome, and the prefix to run it in `package.json` scripts and `Makefile` targets.
+fn biome_install_command(
+    package_manager: Option<PackageManager>,
+) -> RepoResult<(&'static str, [&'static str; 4], &'static str)> {
+    match package_manager {
+        // TODO: generalize to a function to add a dependency
+        Some(PackageManager::Npm) => Ok((
+            "npm",
+            [
+                "install",
+                "--save-dev",
+                "@biomejs/biome",
+                "@cubing/dev-config",
+            ],
+            "bun x @biomejs/biome",
+        )),
+        Some(PackageManager::Bun) => Ok((
+            "bun",
+            [
+                "add",
+                "--development",
+                "@biomejs/biome",
+                "@cubing/dev-config",
+            ],
+            "bun x @biomejs/biome",
+        )),
+        Some(PackageManager::Yarn) => Ok((
+            "yarn",
+            ["add", "--dev", "@biomejs/biome", "@cubing/dev-config"],
+            "npx yarn exec @biomejs/biome",
+        )),
+        Some(PackageManager::Pnpm) => Ok((
+            "pnpm",
+            [
+                "install",
+                "--save-dev",
+                "@biomejs/biome",
+                "@cubing/dev-config",
+            ],
+            "npx pnpm exec biome",
+        )),
+        Some(package_manager) => Err(package_manager.unsupported_error("installing Biome")),
+        None => Err(RepoError::detection_failure(
+            "No JS package detected (run `repo boilerplate package-json` to create `package.json`).",
+        )),
+    }
+}
+
+fn add_biome(template_file_args: TemplateFileArgs<ApplyCommandsArgs>) -> RepoResult<()> {
+    if !matches!(template_file_args.command, TemplateFileCommand::Add(_)) {
+        return biome_json_template().handle_command(template_file_args);
+    }
+    let (binary, args, biome_command_prefix) = biome_install_command(
+        PackageManager::auto_detect_preferred_package_manager_for_ecosystem(Ecosystem::JavaScript),
+    )?;
+    let mut command = PrintableShellCommand::new(binary);
+    command.arg_each(args);
+    command_must_succeed_interactively(command)?;
+    let apply = matches!(&template_file_args.command, TemplateFileCommand::Add(template_file_create_args) if template_file_create_args.custom_args.apply);
+    biome_json_template().handle_command(template_file_args)?;
+    if apply {
+        apply_package_json_scripts(&[
+            ("lint", format!("{} check", biome_command_prefix)),
+            ("format", format!("{} check --write", biome_command_prefix)),
+        ])?;
+        return apply_makefile_targets(&[
+            MakefileTarget {
+                name: "lint-biome",
+                aggregate: Some("lint"),
+                recipe: vec![format!("{} check", biome_command_prefix)],
+            },
+            MakefileTarget {
+                name: "format-biome",
+                aggregate: Some("format"),
+                recipe: vec![format!("{} check --write", biome_command_prefix)],
+            },
+        ]);
+    }
     println!(
         "Use the following commands:
 
@@ -230,49 +1001,90 @@ format:
 ⇥{} check --write
 ",
         biome_command_prefix, biome_command_prefix, biome_command_prefix, biome_command_prefix,
-    )
+    );
+    Ok(())
 }
 
-fn add_tsconfig(template_file_args: TemplateFileArgs<TsconfigArgs>) {
+/// The command to install `@cubing/dev-config` (which the `tsconfig.json` templates extend).
+fn dev_config_install_command(
+    package_manager: Option<PackageManager>,
+) -> RepoResult<(&'static str, [&'static str; 3])> {
+    match package_manager {
+        // TODO: generalize to a function to add a dependency
+        Some(PackageManager::Npm) => Ok(("npm", ["install", "--save-dev", "@cubing/dev-config"])),
+        Some(PackageManager::Bun) => Ok(("bun", ["add", "--development", "@cubing/dev-config"])),
+        Some(PackageManager::Yarn) => Ok(("yarn", ["add", "--dev", "@cubing/dev-config"])),
+        Some(PackageManager::Pnpm) => Ok(("pnpm", ["install", "--save-dev", "@cubing/dev-config"])),
+        Some(package_manager) => {
+            Err(package_manager.unsupported_error("installing `@cubing/dev-config`"))
+        }
+        None => Err(RepoError::detection_failure(
+            "No JS package detected (run `repo boilerplate package-json` to create `package.json`).",
+        )),
+    }
+}
+
+fn add_tsconfig(template_file_args: TemplateFileArgs<TsconfigArgs>) -> RepoResult<()> {
     // Note that we don't install the `typescript` package because
     // `tsconfig.json` is still needed to get VS Code's built-in TypeScript
     // annotations to accept some well-established features like top-level
     // `await` (even if the project itself doesn't use `tsc`).
-    let (binary, args) = match PackageManager::auto_detect_preferred_package_manager_for_ecosystem(
-        Ecosystem::JavaScript,
-    ) {
-        // TODO: generalize to a function to add a dependency
-        Some(PackageManager::Npm) => ("npm", ["install", "--save-dev", "@cubing/dev-config"]),
-        Some(PackageManager::Bun) => ("bun", ["add", "--development", "@cubing/dev-config"]),
-        Some(PackageManager::Yarn) => ("yarn", ["add", "--dev", "@cubing/dev-config"]),
-        Some(PackageManager::Pnpm) => ("pnpm", ["install", "--save-dev", "@cubing/dev-config"]),
-        Some(PackageManager::Cargo) => panic!("unrechachable"),
-        None => {
-            panic!("No JS package detected.")
-        }
-    };
-    PrintableShellCommand::new(binary)
-        .arg_each(args)
-        .spawn()
-        .expect("Could not add development dependency")
-        .wait()
-        .unwrap();
-    tsconfig_template(template_file_args.command.clone()).handle_command(template_file_args);
+    if !matches!(template_file_args.command, TemplateFileCommand::Add(_)) {
+        return tsconfig_template(template_file_args.command.clone())?
+            .handle_command(template_file_args);
+    }
+    let (binary, args) = dev_config_install_command(
+        PackageManager::auto_detect_preferred_package_manager_for_ecosystem(Ecosystem::JavaScript),
+    )?;
+    let mut command = PrintableShellCommand::new(binary);
+    command.arg_each(args);
+    command_must_succeed_interactively(command)?;
+    tsconfig_template(template_file_args.command.clone())?.handle_command(template_file_args)
     // TODO: print `tsc` invocation (requires installation)
 }
 
-fn add_bunfig(template_file_args: TemplateFileArgs) {
-    bunfig_template().handle_command(template_file_args);
+fn add_bunfig(template_file_args: TemplateFileArgs) -> RepoResult<()> {
+    bunfig_template().handle_command(template_file_args)
     // TODO: print `tsc` invocation (requires installation)
 }
 
-fn add_readme_cli_help(template_file_args: TemplateFileArgs) {
-    readme_cli_help_template().handle_command(template_file_args);
+fn add_readme_cli_help(template_file_args: TemplateFileArgs<ReadmeCliHelpArgs>) -> RepoResult<()> {
+    if let TemplateFileCommand::Add(template_file_create_args) = &template_file_args.command {
+        if let Some(discover) = &template_file_create_args.custom_args.discover {
+            let command: Vec<String> = discover.split_whitespace().map(str::to_owned).collect();
+            return add_discovered_help(&command, template_file_create_args.custom_args.max_depth);
+        }
+    }
+    readme_cli_help_template().handle_command(template_file_args)
     // TODO: print `readme-cli-help` invocation
 }
 
-fn add_rust_toolchain(template_file_args: TemplateFileArgs) {
-    rust_toolchain_template().handle_command(template_file_args);
+fn add_rust_toolchain(template_file_args: TemplateFileArgs<ApplyCommandsArgs>) -> RepoResult<()> {
+    let TemplateFileCommand::Add(template_file_create_args) = &template_file_args.command else {
+        return rust_toolchain_template().handle_command(template_file_args);
+    };
+    let apply = template_file_create_args.custom_args.apply;
+    rust_toolchain_template().handle_command(template_file_args)?;
+    if apply {
+        return apply_makefile_targets(&[
+            MakefileTarget {
+                name: "lint-rust",
+                aggregate: Some("lint"),
+                recipe: vec![
+                    "cargo clippy -- --deny warnings".to_owned(),
+                    "cargo fmt --check".to_owned(),
+                ],
+            },
+            MakefileTarget {
+                name: "format-rust",
+                aggregate: Some("format"),
+                recipe: vec![
+                    "cargo clippy --fix --allow-no-vcs".to_owned(),
+                    "cargo fmt".to_owned(),
+                ],
+            },
+        ]);
+    }
     // TODO: mention `test-cargo-doc`?
     println!(
         "Use the following commands:
@@ -290,26 +1102,311 @@ format-rust:
 ⇥cargo clippy --fix --allow-no-vcs
 ⇥cargo fmt
 "
-    )
+    );
+    Ok(())
+}
+
+fn init_file(relative_path: &str, bytes: &'static [u8]) -> TemplateFile<'static> {
+    TemplateFile {
+        relative_path: PathBuf::from(relative_path),
+        bytes: Cow::Borrowed(bytes),
+    }
+}
+
+/// Skipped if the folder is already a repository for `vcs_kind`. For `jj`, an existing `.git` folder is colocated.
+fn init_vcs(vcs_kind: VcsKind) -> RepoResult<()> {
+    let repo_folder = match vcs_kind {
+        VcsKind::Git => ".git",
+        VcsKind::Jj => ".jj",
+        VcsKind::Mercurial => ".hg",
+    };
+    if exists(repo_folder)? {
+        eprintln!(
+            "Skipping VCS initialization (already a repository): {}",
+            repo_folder
+        );
+        return Ok(());
+    }
+    let mut command = match vcs_kind {
+        VcsKind::Git => PrintableShellCommand::new("git"),
+        VcsKind::Jj => {
+            let mut command = PrintableShellCommand::new("jj");
+            command.arg("git");
+            command
+        }
+        VcsKind::Mercurial => PrintableShellCommand::new("hg"),
+    };
+    command.arg("init");
+    if matches!(vcs_kind, VcsKind::Jj) && exists(".git")? {
+        command.arg("--colocate");
+    }
+    command_must_succeed_interactively(command)
+}
+
+fn boilerplate_init(init_args: InitArgs) -> RepoResult<()> {
+    let mut template_variable_args = init_args.template_variable_args;
+    if template_variable_args.ecosystems.is_empty() {
+        template_variable_args.ecosystems = match init_args.preset {
+            InitPreset::RustCli => vec![Ecosystem::Rust],
+            InitPreset::TsLib => vec![Ecosystem::JavaScript],
+            InitPreset::RustNpmBinary => vec![Ecosystem::Rust, Ecosystem::JavaScript],
+        };
+    }
+    if let Some(vcs_kind) = init_args.init_vcs {
+        init_vcs(vcs_kind)?;
+    }
+    let template_variables = TemplateVariables::from_args_or_detect(&template_variable_args);
+    let ci_forge = template_variables.forge;
+
+    // Scaffolding that is expected to diverge from the template right away.
+    let mut scaffolding: Vec<TemplateFile> = vec![];
+    // Boilerplate that is recorded in `.config/repo-boilerplate.json` for `repo boilerplate upgrade`.
+    let mut boilerplate: Vec<TemplateFile> = vec![];
+    let rust_files = [
+        init_file(
+            "./Cargo.toml",
+            include_bytes!("../templates/init/Cargo.toml.template"),
+        ),
+        init_file(
+            "./src/main.rs",
+            include_bytes!("../templates/init/src/main.rs"),
+        ),
+    ];
+    match init_args.preset {
+        InitPreset::RustCli => {
+            scaffolding.extend(rust_files);
+            scaffolding.push(init_file(
+                MAKEFILE_PATH,
+                include_bytes!("../templates/init/Makefile.rust-cli"),
+            ));
+            boilerplate.push(rust_toolchain_template());
+        }
+        InitPreset::TsLib => {
+            scaffolding.extend([
+                init_file(
+                    PACKAGE_JSON_PATH,
+                    include_bytes!("../templates/init/package.ts-lib.json"),
+                ),
+                init_file(
+                    "./src/index.ts",
+                    include_bytes!("../templates/init/src/index.ts"),
+                ),
+                init_file(
+                    "./src/index.test.ts",
+                    include_bytes!("../templates/init/src/index.test.ts"),
+                ),
+                init_file(
+                    MAKEFILE_PATH,
+                    include_bytes!("../templates/init/Makefile.ts-lib"),
+                ),
+            ]);
+            boilerplate.extend([
+                biome_json_template(),
+                tsconfig_template_variant(false, ESModule::default()),
+                bunfig_template(),
+            ]);
+        }
+        InitPreset::RustNpmBinary => {
+            scaffolding.extend(rust_files);
+            scaffolding.extend([
+                init_file(
+                    PACKAGE_JSON_PATH,
+                    include_bytes!("../templates/init/package.rust+npm-binary.json"),
+                ),
+                init_file(
+                    MAKEFILE_PATH,
+                    include_bytes!("../templates/init/Makefile.rust+npm-binary"),
+                ),
+            ]);
+            boilerplate.extend([
+                rust_toolchain_template(),
+                biome_json_template(),
+                bunfig_template(),
+                readme_cli_help_template(),
+            ]);
+        }
+    }
+    scaffolding.extend([
+        init_file(
+            "./.config/repo.json",
+            include_bytes!("../templates/init/repo.json"),
+        ),
+        init_file("./README.md", include_bytes!("../templates/init/README.md")),
+    ]);
+    if ci_forge == VCSForge::GitHub && !matches!(init_args.preset, InitPreset::TsLib) {
+        boilerplate.push(publish_github_release_template());
+    }
+
+    for template_file in &scaffolding {
+        template_file.add_if_missing(&template_variables, &template_variable_args, false)?;
+    }
+    if write_gitignore(&template_variables.ecosystems)? {
+        eprintln!("Updated: {}", GITIGNORE_PATH);
+    }
+    for template_file in &boilerplate {
+        template_file.add_if_missing(&template_variables, &template_variable_args, true)?;
+    }
+    // The CI workflow is generated from the files above, so it must be created last.
+    ci_template(&ci_forge, &template_variables).add_if_missing(
+        &template_variables,
+        &template_variable_args,
+        true,
+    )?;
+
+    if init_args.no_setup {
+        return Ok(());
+    }
+    setup_inferred()
+}
+
+fn boilerplate_ci(ci_args: CIArgs) -> RepoResult<()> {
+    let template_file_command = match ci_args.command {
+        CICommand::TemplateFile(template_file_command) => template_file_command,
+        CICommand::Pin(ci_pin_args) => {
+            return pin_workflow_actions(
+                ci_pin_args.workflow_files_args.files,
+                &ci_pin_args.workflow_files_args.remote_base,
+                ci_pin_args.latest,
+            );
+        }
+        CICommand::Lint(workflow_files_args) => {
+            return lint_workflow_actions(
+                workflow_files_args.files,
+                &workflow_files_args.remote_base,
+            );
+        }
+    };
+    let add_forge = match &template_file_command {
+        TemplateFileCommand::Add(template_file_create_args) => {
+            template_file_create_args.template_variable_args.forge
+        }
+        _ => None,
+    };
+    let forges = match ci_args.forge {
+        Some(CIForge::All) => vec![
+            VCSForge::GitHub,
+            VCSForge::Codeberg,
+            VCSForge::GitLab,
+            VCSForge::Forgejo,
+        ],
+        Some(CIForge::Forge(forge)) => vec![forge],
+        None => vec![add_forge.or_else(VCSForge::auto_detect).unwrap_or_default()],
+    };
+    for forge in forges {
+        let mut template_file_args = TemplateFileArgs {
+            command: template_file_command.clone(),
+        };
+        let template_variable_args = match &mut template_file_args.command {
+            TemplateFileCommand::Add(template_file_create_args) => {
+                template_file_create_args.template_variable_args.forge = Some(forge);
+                template_file_create_args.template_variable_args.clone()
+            }
+            TemplateFileCommand::Diff(template_variable_args) => template_variable_args.clone(),
+            // `edit` and `reveal` only use the path.
+            _ => TemplateVariableArgs::default(),
+        };
+        let template_variables = TemplateVariables::from_args_or_detect(&template_variable_args);
+        ci_template(&forge, &template_variables).handle_command(template_file_args)?;
+    }
+    Ok(())
 }
 
 // TODO: use traits to abstract across ecosystems
-pub(crate) fn boilerplate(boilerplate_args: BoilerplateArgs) {
+pub(crate) fn boilerplate(boilerplate_args: BoilerplateArgs) -> RepoResult<()> {
     match boilerplate_args.command {
-        BoilerplateCommand::CI(ci_args) => {
-            ci_template(&ci_args.forge).handle_command(ci_args.template_file_args);
-        }
+        BoilerplateCommand::CI(ci_args) => boilerplate_ci(ci_args),
         BoilerplateCommand::AutoPublishGithubRelease(template_file_args) => {
-            publish_github_release_template().handle_command(template_file_args);
+            publish_github_release_template().handle_command(template_file_args)
         }
         BoilerplateCommand::Biome(template_file_args) => add_biome(template_file_args),
         BoilerplateCommand::Tsconfig(template_file_args) => add_tsconfig(template_file_args),
         BoilerplateCommand::Bunfig(template_file_args) => add_bunfig(template_file_args),
+        BoilerplateCommand::ReleaseBinaries(template_file_args) => {
+            add_release_binaries(template_file_args)
+        }
+        BoilerplateCommand::License(template_file_args) => add_license(template_file_args),
+        BoilerplateCommand::PackageJson(package_json_args) => {
+            update_package_json(PackageJsonFields {
+                name: package_json_args.name,
+                package_manager: package_json_args.package_manager_args.package_manager,
+            })
+        }
+        BoilerplateCommand::LicenseHeaders(license_headers_args) => {
+            match license_headers_args.command {
+                LicenseHeadersCommand::Check(files_args) => {
+                    license_headers(files_args.files, files_args.license, false)
+                }
+                LicenseHeadersCommand::Apply(files_args) => {
+                    license_headers(files_args.files, files_args.license, true)
+                }
+            }
+        }
+        BoilerplateCommand::CargoPackage(cargo_package_args) => update_cargo_toml_package(
+            PackageFields {
+                repository: cargo_package_args.repository,
+                license: cargo_package_args.license,
+                description: cargo_package_args.description,
+            }
+            .or_detect(),
+            !cargo_package_args.no_binstall_metadata,
+            cargo_package_args.size_optimized_release_profile,
+        ),
+        BoilerplateCommand::Gitignore(template_variable_args) => {
+            boilerplate_gitignore(template_variable_args)
+        }
         BoilerplateCommand::ReadmeCliHelp(template_file_args) => {
             add_readme_cli_help(template_file_args)
         }
+        BoilerplateCommand::PostVersionHook(template_file_args) => {
+            post_version_hook_bundle().handle_command(template_file_args)
+        }
         BoilerplateCommand::RustToolchain(template_file_args) => {
             add_rust_toolchain(template_file_args)
         }
+        BoilerplateCommand::List => list_templates(),
+        BoilerplateCommand::Status(template_variable_args) => {
+            boilerplate_status(template_variable_args)
+        }
+        BoilerplateCommand::Upgrade(template_variable_args) => {
+            boilerplate_upgrade(template_variable_args)
+        }
+        BoilerplateCommand::Init(init_args) => boilerplate_init(init_args),
+        BoilerplateCommand::Pack(pack_args) => match pack_args.command {
+            PackCommand::Add(pack_add_args) => add_template_pack(
+                &pack_add_args.source,
+                pack_add_args.rev.as_deref(),
+                pack_add_args.name,
+            ),
+        },
+        BoilerplateCommand::Extra(args) => extra_template(args),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::boilerplate::{biome_install_command, dev_config_install_command},
+        common::{error::RepoErrorKind, package_manager::PackageManager},
     };
+
+    #[test]
+    fn test_unsupported_package_manager_for_js_dev_dependencies() {
+        for package_manager in [
+            PackageManager::Cargo,
+            PackageManager::Deno,
+            PackageManager::Bundler,
+        ] {
+            let error = biome_install_command(Some(package_manager.clone())).unwrap_err();
+            assert_eq!(error.kind, RepoErrorKind::UserError);
+            assert!(error
+                .message
+                .contains("is not supported for installing Biome"));
+            let error = dev_config_install_command(Some(package_manager)).unwrap_err();
+            assert_eq!(error.kind, RepoErrorKind::UserError);
+        }
+        assert_eq!(
+            biome_install_command(None).unwrap_err().kind,
+            RepoErrorKind::DetectionFailure
+        );
+    }
 }
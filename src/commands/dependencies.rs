@@ -10,10 +10,12 @@ use serde::{Deserialize, Serialize};
 use crate::{
     commands::version::{detect_ecosystem_by_getting_version, CommitOperationArgs},
     common::{
+        command::command_must_get_stdout,
         commit_wrapped_operation::CommitWrappedOperation,
-        ecosystem::EcosystemArgs,
-        inference::get_stdout,
+        ecosystem::{Ecosystem, EcosystemArgs},
+        error::{RepoError, RepoResult},
         package_manager::{PackageManager, PackageManagerArgs},
+        registry::RegistryArgs,
     },
 };
 
@@ -34,6 +36,9 @@ pub(crate) struct DependenciesArgs {
     #[command(flatten)]
     package_manager_args: PackageManagerArgs,
 
+    #[command(flatten)]
+    registry_args: RegistryArgs,
+
     #[command(subcommand)]
     command: DependenciesCommand,
 }
@@ -138,26 +143,57 @@ impl PackageJSONSubset {
     }
 }
 
-fn must_get_package_json() -> PackageJSONSubset {
+fn must_get_package_json() -> RepoResult<PackageJSONSubset> {
     let mut npm_command = PrintableShellCommand::new("npm");
     npm_command.args(["root"]);
-    let node_modules_folder = get_stdout(npm_command).unwrap();
-    let package_json_path = PathBuf::from(node_modules_folder)
+    let node_modules_folder = command_must_get_stdout(npm_command)?;
+    let Some(package_folder) = PathBuf::from(&node_modules_folder)
         .parent()
-        .unwrap()
-        .join("package.json");
-    let file = File::open(package_json_path).unwrap();
+        .map(PathBuf::from)
+    else {
+        return Err(RepoError::detection_failure(format!(
+            "Could not get the package folder for: {}",
+            node_modules_folder
+        )));
+    };
+    let package_json_path = package_folder.join("package.json");
+    let file = File::open(&package_json_path).map_err(|e| {
+        RepoError::file_system(format!(
+            "Could not open `{}`: {}",
+            package_json_path.to_string_lossy(),
+            e
+        ))
+    })?;
     let reader = BufReader::new(file);
 
     // TODO: get a stream instead?
-    serde_json::from_reader(reader).unwrap()
+    serde_json::from_reader(reader).map_err(|e| {
+        RepoError::file_system(format!(
+            "Could not parse `{}`: {}",
+            package_json_path.to_string_lossy(),
+            e
+        ))
+    })
 }
 
-fn npm_show_version(dependency_name: &DependencyName) -> Version {
+fn npm_show_version(
+    dependency_name: &DependencyName,
+    registry: Option<&str>,
+) -> RepoResult<Version> {
     let mut npm_command = PrintableShellCommand::new("npm");
+    npm_command.arg("show");
+    if let Some(registry) = registry {
+        npm_command.args(["--registry", registry]);
+    }
     // `--` is needed because packages can start with `-` and we want to prevent any chance of argument injection.
-    npm_command.args(["show", "--", &dependency_name.0, "version"]);
-    Version::parse(get_stdout(npm_command).unwrap().trim()).unwrap()
+    npm_command.args(["--", &dependency_name.0, "version"]);
+    let version = command_must_get_stdout(npm_command)?;
+    Version::parse(version.trim()).map_err(|e| {
+        RepoError::tool_failed(format!(
+            "`npm show` returned an invalid version ({}): {}",
+            version, e
+        ))
+    })
 }
 
 fn npm_package_contraint_arg(
@@ -187,11 +223,14 @@ fn npm_install(
     dependency_type: &NpmDependencyType,
     dependencies_roll_args: &DependenciesRollArgs,
     new_version: &Version,
-) -> String {
+    registry: Option<&str>,
+) -> RepoResult<String> {
     let mut npm_command = PrintableShellCommand::new("npm");
+    npm_command.args(["install", dependency_type.npm_install_arg()]);
+    if let Some(registry) = registry {
+        npm_command.args(["--registry", registry]);
+    }
     npm_command.args([
-        "install",
-        dependency_type.npm_install_arg(),
         // `--` is needed because packages can start with `-` and we want to prevent any chance of argument injection.
         "--",
         &npm_package_contraint_arg(dependencies_roll_args, new_version),
@@ -202,8 +241,8 @@ fn npm_install(
             ..Default::default()
         })
         .unwrap();
-    let _ = get_stdout(npm_command).unwrap();
-    command_string
+    command_must_get_stdout(npm_command)?;
+    Ok(command_string)
 }
 
 type CommandStringWithNote = (String, Option<String>);
@@ -212,12 +251,16 @@ fn try_bun_add_for_roll(
     dependency_type: &NpmDependencyType,
     dependencies_roll_args: &DependenciesRollArgs,
     new_version: &Version,
-) -> Result<CommandStringWithNote, ()> {
+    registry: Option<&str>,
+) -> RepoResult<CommandStringWithNote> {
     let mut bun_add_command = PrintableShellCommand::new("bun");
     bun_add_command.arg("add");
     if let Some(arg) = dependency_type.bun_add_arg() {
         bun_add_command.arg(arg);
     }
+    if let Some(registry) = registry {
+        bun_add_command.args(["--registry", registry]);
+    }
     // `--` is needed because packages can start with `-` and we want to prevent any chance of argument injection.
     bun_add_command.arg("--");
     let dependency_arg = npm_package_contraint_arg(dependencies_roll_args, new_version);
@@ -231,6 +274,9 @@ fn try_bun_add_for_roll(
     // Needed to remove transitive dependencies that have become unused.
     let mut bun_install_command = PrintableShellCommand::new("bun");
     bun_install_command.args(["install"]);
+    if let Some(registry) = registry {
+        bun_install_command.args(["--registry", registry]);
+    }
 
     let command_string = [&bun_add_command, &bun_dedupe_command, &bun_install_command]
         .map(|v| {
@@ -242,15 +288,9 @@ fn try_bun_add_for_roll(
         })
         .join(" && ");
 
-    let Some(_) = get_stdout(bun_add_command) else {
-        return Err(());
-    };
-    let Some(_) = get_stdout(bun_dedupe_command) else {
-        return Err(());
-    };
-    let Some(_) = get_stdout(bun_install_command) else {
-        return Err(());
-    };
+    command_must_get_stdout(bun_add_command)?;
+    command_must_get_stdout(bun_dedupe_command)?;
+    command_must_get_stdout(bun_install_command)?;
 
     Ok((
         command_string,
@@ -258,12 +298,10 @@ fn try_bun_add_for_roll(
     ))
 }
 
-fn bun_pm_cache_rm() -> Result<(), ()> {
+fn bun_pm_cache_rm() -> RepoResult<()> {
     let mut bun_command = PrintableShellCommand::new("bun");
     bun_command.args(["pm", "cache", "rm"]);
-    let Some(_) = get_stdout(bun_command) else {
-        return Err(());
-    };
+    command_must_get_stdout(bun_command)?;
     Ok(())
 }
 
@@ -271,19 +309,30 @@ fn bun_add_for_roll(
     dependency_type: &NpmDependencyType,
     dependencies_roll_args: &DependenciesRollArgs,
     new_version: &Version,
-) -> CommandStringWithNote {
+    registry: Option<&str>,
+) -> RepoResult<CommandStringWithNote> {
     // TODO: sniff for out-of-date-cache by inspecting `stdout`.
-    if let Ok(s) = try_bun_add_for_roll(dependency_type, dependencies_roll_args, new_version) {
-        return s;
+    if let Ok(s) = try_bun_add_for_roll(
+        dependency_type,
+        dependencies_roll_args,
+        new_version,
+        registry,
+    ) {
+        return Ok(s);
     };
     eprintln!(
         "Updating the dependency version failed. Clearing `bun`'s cache and trying one more time."
     );
-    bun_pm_cache_rm().unwrap();
-    try_bun_add_for_roll(dependency_type, dependencies_roll_args, new_version).unwrap()
+    bun_pm_cache_rm()?;
+    try_bun_add_for_roll(
+        dependency_type,
+        dependencies_roll_args,
+        new_version,
+        registry,
+    )
 }
 
-pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Result<(), String> {
+pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> RepoResult<()> {
     match dependencies_args.command {
         DependenciesCommand::Roll(dependencies_command_args) => {
             let package_manager = match &dependencies_args.package_manager_args.package_manager {
@@ -293,7 +342,7 @@ pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Resul
                     let Some((ecosystem, _)) =
                         detect_ecosystem_by_getting_version(&dependencies_args.ecosystem_args)
                     else {
-                        return Err("Could not detect ecosystem.".to_owned());
+                        return Err(RepoError::detection_failure("Could not detect ecosystem."));
                     };
 
                     let Some(package_manager) =
@@ -301,7 +350,9 @@ pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Resul
                             ecosystem,
                         )
                     else {
-                        return Err("Could not detect package manager.".to_owned());
+                        return Err(RepoError::detection_failure(
+                            "Could not detect package manager.",
+                        ));
                     };
 
                     package_manager
@@ -309,11 +360,15 @@ pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Resul
             };
 
             let dependency_name = &dependencies_command_args.roll_args.dependency_name;
+            let registry = dependencies_args
+                .registry_args
+                .registry_for_ecosystem(Ecosystem::JavaScript)?;
+            let registry = registry.as_deref();
 
             match package_manager {
                 PackageManager::Npm | PackageManager::Bun => {
-                    let package_json = must_get_package_json();
-                    let new_version = &npm_show_version(dependency_name);
+                    let package_json = must_get_package_json()?;
+                    let new_version = &npm_show_version(dependency_name, registry)?;
                     // TODO: compare version against installed.
                     let mut any_rolled = false;
                     for npm_dependency_type in NpmDependencyType::all_types() {
@@ -321,37 +376,35 @@ pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Resul
                         {
                             let commit_wrapped_operation = CommitWrappedOperation::try_from(
                                 &dependencies_command_args.common_args.commit_args,
-                            )
-                            .unwrap();
-                            commit_wrapped_operation
-                                .perform_operation(&|| {
-                                    let (command, note) = if package_manager == PackageManager::Npm
-                                    {
-                                        (
-                                            npm_install(
-                                                npm_dependency_type,
-                                                &dependencies_command_args.roll_args,
-                                                new_version,
-                                            ),
-                                            None,
-                                        )
-                                    } else {
-                                        bun_add_for_roll(
+                            )?;
+                            commit_wrapped_operation.perform_operation(&|| {
+                                let (command, note) = if package_manager == PackageManager::Npm {
+                                    (
+                                        npm_install(
                                             npm_dependency_type,
                                             &dependencies_command_args.roll_args,
                                             new_version,
-                                        )
-                                    };
-                                    // TODO: also include the old version in the printed message and commit message.
-                                    println!("{}", command);
-                                    // TODO: can this ever generate a command with missing escapes?
-                                    Ok(format!(
-                                        "`{}` (roll){}",
-                                        command,
-                                        note.map(|s| format!("\n\n{}", s)).unwrap_or_default()
-                                    ))
-                                })
-                                .unwrap();
+                                            registry,
+                                        )?,
+                                        None,
+                                    )
+                                } else {
+                                    bun_add_for_roll(
+                                        npm_dependency_type,
+                                        &dependencies_command_args.roll_args,
+                                        new_version,
+                                        registry,
+                                    )?
+                                };
+                                // TODO: also include the old version in the printed message and commit message.
+                                println!("{}", command);
+                                // TODO: can this ever generate a command with missing escapes?
+                                Ok(format!(
+                                    "`{}` (roll){}",
+                                    command,
+                                    note.map(|s| format!("\n\n{}", s)).unwrap_or_default()
+                                ))
+                            })?;
                             any_rolled = true;
                         }
                     }
@@ -363,10 +416,10 @@ pub(crate) fn dependencies_command(dependencies_args: DependenciesArgs) -> Resul
                     }
                     Ok(())
                 }
-                package_manager => Err(format!(
+                package_manager => Err(RepoError::user_error(format!(
                     "Dependency rolling is not implemented for package manager: {}",
                     package_manager
-                )),
+                ))),
             }
         }
     }
@@ -2,6 +2,8 @@ pub(crate) mod boilerplate;
 pub(crate) mod dependencies;
 pub(crate) mod print_schema;
 pub(crate) mod publish;
+pub(crate) mod readme_cli_help;
+pub(crate) mod release;
 pub(crate) mod setup;
 pub(crate) mod vcs;
 pub(crate) mod version;
@@ -1,7 +1,13 @@
 use clap::{Args, Subcommand};
 use schemars::schema_for;
 
-use crate::{commands::version::PostVersionInfo, common::config::Config};
+use crate::{
+    commands::version::PostVersionInfo,
+    common::{
+        config::Config,
+        error::{RepoError, RepoResult},
+    },
+};
 
 #[derive(Args, Debug)]
 pub(crate) struct PrintSchemaArgs {
@@ -16,10 +22,14 @@ enum Schema {
     PostVersion,
 }
 
-pub fn print_schema(args: PrintSchemaArgs) {
+pub fn print_schema(args: PrintSchemaArgs) -> RepoResult<()> {
     let schema = match args.schema {
         Schema::Config => schema_for!(Config),
         Schema::PostVersion => schema_for!(PostVersionInfo),
     };
-    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schema).map_err(|e| RepoError::internal(e.to_string()))?
+    );
+    Ok(())
 }
@@ -2,39 +2,87 @@ use clap::Args;
 use printable_shell_command::PrintableShellCommand;
 
 use crate::common::{
-    debug::DebugPrintable,
+    command::command_must_succeed_interactively,
     ecosystem::{Ecosystem, EcosystemArgs},
+    error::RepoResult,
+    package_manager::{DENO_JSONC_PATH, DENO_JSON_PATH},
+    registry::RegistryArgs,
 };
 
-use super::version::must_detect_ecosystem_by_getting_version;
+use super::version::{gemspec_path, jsr_manifest_path, must_detect_ecosystem_by_getting_version};
 
 #[derive(Args, Debug)]
 pub(crate) struct PublishArgs {
     #[command(flatten)]
     ecosystem_args: EcosystemArgs,
+
+    #[command(flatten)]
+    registry_args: RegistryArgs,
 }
 
 // TODO: use traits to abstract across ecosystems
 // TODO: support cross-checking versions across ecosystems
-pub(crate) fn publish_command(publish_args: PublishArgs) {
-    match must_detect_ecosystem_by_getting_version(&publish_args.ecosystem_args) {
-        (Ecosystem::JavaScript, _) => {
-            PrintableShellCommand::new("npm")
-                .arg("publish")
-                .debug_print()
-                .spawn()
-                .expect("Could not publish using `npm`")
-                .wait()
-                .unwrap();
+pub(crate) fn publish_command(publish_args: PublishArgs) -> RepoResult<()> {
+    let (ecosystem, version) =
+        must_detect_ecosystem_by_getting_version(&publish_args.ecosystem_args)?;
+    let registry = publish_args
+        .registry_args
+        .registry_for_ecosystem(ecosystem)?;
+    match ecosystem {
+        Ecosystem::JavaScript => {
+            let mut command = PrintableShellCommand::new("npm");
+            command.arg("publish");
+            if let Some(registry) = registry {
+                command.args(["--registry", &registry]);
+            }
+            command_must_succeed_interactively(command)
+        }
+        Ecosystem::Jsr => {
+            // `deno publish` is preferred if there is a Deno config, but `jsr.json` can also be published using `npx jsr publish`.
+            let mut command = match jsr_manifest_path() {
+                Some(DENO_JSON_PATH | DENO_JSONC_PATH) => PrintableShellCommand::new("deno"),
+                _ => {
+                    let mut command = PrintableShellCommand::new("npx");
+                    command.arg("jsr");
+                    command
+                }
+            };
+            command.arg("publish");
+            command_must_succeed_interactively(command)
+        }
+        Ecosystem::Ruby => {
+            let gemspec_path = gemspec_path()?;
+            let gem_path = gemspec_path.with_file_name(format!(
+                "{}-{}.gem",
+                gemspec_path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                version
+            ));
+
+            let mut build_command = PrintableShellCommand::new("gem");
+            build_command.arg("build");
+            build_command.arg(&gemspec_path);
+            build_command.arg("--output");
+            build_command.arg(&gem_path);
+            command_must_succeed_interactively(build_command)?;
+
+            let mut push_command = PrintableShellCommand::new("gem");
+            push_command.arg("push");
+            if let Some(registry) = registry {
+                push_command.args(["--host", &registry]);
+            }
+            push_command.arg(&gem_path);
+            command_must_succeed_interactively(push_command)
         }
-        (Ecosystem::Rust, _) => {
-            PrintableShellCommand::new("cargo")
-                .arg("publish")
-                .debug_print()
-                .spawn()
-                .expect("Could not publish using `cargo`")
-                .wait()
-                .unwrap();
+        Ecosystem::Rust => {
+            let mut command = PrintableShellCommand::new("cargo");
+            command.arg("publish");
+            if let Some(registry) = registry {
+                command.args(["--registry", &registry]);
+            }
+            command_must_succeed_interactively(command)
         }
     }
 }
@@ -0,0 +1,24 @@
+use clap::{Args, Subcommand};
+
+use crate::common::{error::RepoResult, readme_cli_help::sync_readme_cli_help};
+
+#[derive(Args, Debug)]
+pub(crate) struct ReadmeCliHelpArgs {
+    #[command(subcommand)]
+    command: ReadmeCliHelpCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReadmeCliHelpCommand {
+    /// Run the command for each code fence in `.config/readme-cli-help.json` and replace the fence contents with its output.
+    Update,
+    /// Check that each code fence in `.config/readme-cli-help.json` matches the output of its command. Exits with code 8 if any differ.
+    Check,
+}
+
+pub(crate) fn readme_cli_help_command(readme_cli_help_args: ReadmeCliHelpArgs) -> RepoResult<()> {
+    match readme_cli_help_args.command {
+        ReadmeCliHelpCommand::Update => sync_readme_cli_help(false),
+        ReadmeCliHelpCommand::Check => sync_readme_cli_help(true),
+    }
+}
@@ -0,0 +1,317 @@
+use std::{
+    fs::{exists, read_to_string},
+    path::PathBuf,
+};
+
+use clap::{Args, Subcommand, ValueEnum};
+use printable_shell_command::PrintableShellCommand;
+
+use crate::common::{
+    command::{command_must_get_stdout, command_must_succeed_interactively},
+    error::{RepoError, RepoResult},
+    forge::VCSForge,
+    vcs::{vcs_or_infer, VcsKind},
+};
+
+const CHANGELOG_PATH: &str = "./CHANGELOG.md";
+
+#[derive(Args, Debug)]
+pub(crate) struct ReleaseArgs {
+    #[command(subcommand)]
+    command: ReleaseCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReleaseCommand {
+    /// Create a forge release for the current tag, using `gh` (GitHub), `glab` (GitLab), or `tea` (Codeberg/Forgejo).
+    Create(ReleaseCreateArgs),
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct ReleaseCreateArgs {
+    /// Files to upload as release artifacts.
+    artifacts: Vec<PathBuf>,
+
+    /// Defaults to the forge of the `origin` remote.
+    #[clap(long)]
+    forge: Option<VCSForge>,
+
+    /// Defaults to the tag of the latest commit.
+    #[clap(long)]
+    tag: Option<String>,
+
+    /// Defaults to the matching `CHANGELOG.md` section if there is one, else the commits since the previous tag.
+    #[clap(long)]
+    notes_from: Option<ReleaseNotesSource>,
+
+    #[clap(long)]
+    draft: bool,
+
+    #[clap(long)]
+    pub use_vcs: Option<VcsKind>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+enum ReleaseNotesSource {
+    Changelog,
+    Commits,
+}
+
+fn current_tag(vcs: VcsKind) -> RepoResult<String> {
+    let tags = match vcs {
+        VcsKind::Git => {
+            let mut git_command = PrintableShellCommand::new("git");
+            git_command.args(["tag", "--points-at", "HEAD"]);
+            command_must_get_stdout(git_command)?
+        }
+        VcsKind::Jj => {
+            let mut jj_command = PrintableShellCommand::new("jj");
+            jj_command.args(["log", "--no-graph", "--color=never", "--limit=1"]);
+            jj_command.args([
+                "--revisions",
+                "::@ & ((~description(exact:\"\") & ~empty()) | merges())",
+            ]);
+            jj_command.args(["--template", "tags.map(|t| t.name()).join(\"\\n\")"]);
+            command_must_get_stdout(jj_command)?
+        }
+        VcsKind::Mercurial => {
+            return Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            ))
+        }
+    };
+    let Some(tag) = tags.lines().map(str::trim).find(|tag| !tag.is_empty()) else {
+        return Err(RepoError::detection_failure(
+            "The latest commit has no tag. Tag it or pass `--tag`.",
+        ));
+    };
+    Ok(tag.to_owned())
+}
+
+// Tags like `v1.2.3-dev` would otherwise be parsed using the `-` (parent) operator.
+fn jj_symbol(tag: &str) -> String {
+    format!("{:?}", tag)
+}
+
+fn previous_tag(vcs: VcsKind, tag: &str) -> RepoResult<Option<String>> {
+    let tags = match vcs {
+        VcsKind::Git => {
+            let mut git_command = PrintableShellCommand::new("git");
+            git_command.args(["describe", "--tags", "--abbrev=0"]);
+            git_command.arg(format!("{}^", tag));
+            // There is no previous tag for the first release.
+            command_must_get_stdout(git_command).ok()
+        }
+        VcsKind::Jj => {
+            let mut jj_command = PrintableShellCommand::new("jj");
+            jj_command.args(["log", "--no-graph", "--color=never"]);
+            jj_command.args([
+                "--revisions",
+                &format!("latest(tags() & ::{}-)", jj_symbol(tag)),
+            ]);
+            jj_command.args(["--template", "tags.map(|t| t.name()).join(\"\\n\")"]);
+            Some(command_must_get_stdout(jj_command)?)
+        }
+        VcsKind::Mercurial => {
+            return Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            ))
+        }
+    };
+    Ok(tags.and_then(|tags| {
+        tags.lines()
+            .map(str::trim)
+            .find(|tag| !tag.is_empty())
+            .map(str::to_owned)
+    }))
+}
+
+fn commit_subjects_since_previous_tag(vcs: VcsKind, tag: &str) -> RepoResult<Vec<String>> {
+    let previous_tag = previous_tag(vcs, tag)?;
+    let subjects = match vcs {
+        VcsKind::Git => {
+            let mut git_command = PrintableShellCommand::new("git");
+            git_command.args(["log", "--format=%s"]);
+            git_command.arg(match &previous_tag {
+                Some(previous_tag) => format!("{}..{}", previous_tag, tag),
+                None => tag.to_owned(),
+            });
+            command_must_get_stdout(git_command)?
+        }
+        VcsKind::Jj => {
+            let mut jj_command = PrintableShellCommand::new("jj");
+            jj_command.args(["log", "--no-graph", "--color=never"]);
+            jj_command.args([
+                "--revisions",
+                &match &previous_tag {
+                    Some(previous_tag) => {
+                        format!("{}..{} & ~empty()", jj_symbol(previous_tag), jj_symbol(tag))
+                    }
+                    None => format!("::{} & ~empty()", jj_symbol(tag)),
+                },
+            ]);
+            jj_command.args(["--template", "description.first_line() ++ \"\\n\""]);
+            command_must_get_stdout(jj_command)?
+        }
+        VcsKind::Mercurial => {
+            return Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            ))
+        }
+    };
+    Ok(subjects
+        .lines()
+        .map(str::trim)
+        .filter(|subject| !subject.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Returns the body of the `CHANGELOG.md` section whose heading names the given tag (with or without its `v` prefix), e.g. `## v1.2.3`, `## [1.2.3] - 2025-01-01`.
+fn changelog_section(changelog: &str, tag: &str) -> Option<String> {
+    let version = tag.strip_prefix("v").unwrap_or(tag);
+    let names_version = |heading: &str| {
+        heading
+            .split(|c: char| c.is_whitespace() || "[]()#".contains(c))
+            .any(|word| word.strip_prefix("v").unwrap_or(word) == version)
+    };
+
+    let mut section: Option<(usize, Vec<&str>)> = None;
+    for line in changelog.lines() {
+        let heading_level = line.chars().take_while(|c| *c == '#').count();
+        let is_heading = heading_level > 0 && line[heading_level..].starts_with(' ');
+        match &mut section {
+            None => {
+                if is_heading && names_version(&line[heading_level..]) {
+                    section = Some((heading_level, vec![]));
+                }
+            }
+            Some((section_level, lines)) => {
+                if is_heading && heading_level <= *section_level {
+                    break;
+                }
+                lines.push(line);
+            }
+        }
+    }
+    let body = section?.1.join("\n").trim().to_owned();
+    if body.is_empty() {
+        None
+    } else {
+        Some(body)
+    }
+}
+
+fn release_notes(
+    vcs: VcsKind,
+    tag: &str,
+    notes_from: Option<ReleaseNotesSource>,
+) -> RepoResult<String> {
+    if notes_from != Some(ReleaseNotesSource::Commits) {
+        let changelog = if exists(CHANGELOG_PATH)? {
+            Some(read_to_string(CHANGELOG_PATH)?)
+        } else {
+            None
+        };
+        match changelog.and_then(|changelog| changelog_section(&changelog, tag)) {
+            Some(section) => return Ok(section),
+            None => {
+                if notes_from == Some(ReleaseNotesSource::Changelog) {
+                    return Err(RepoError::detection_failure(format!(
+                        "Could not find a section for `{}` in: {}",
+                        tag, CHANGELOG_PATH
+                    )));
+                }
+            }
+        }
+    }
+    Ok(commit_subjects_since_previous_tag(vcs, tag)?
+        .iter()
+        .map(|subject| format!("- {}", subject))
+        .collect::<Vec<String>>()
+        .join("\n"))
+}
+
+fn release_create(release_create_args: ReleaseCreateArgs) -> RepoResult<()> {
+    for artifact in &release_create_args.artifacts {
+        if !exists(artifact)? {
+            return Err(RepoError::user_error(format!(
+                "Artifact does not exist: {}",
+                artifact.to_string_lossy()
+            )));
+        }
+    }
+    let Some(forge) = release_create_args.forge.or_else(VCSForge::auto_detect) else {
+        return Err(RepoError::detection_failure(
+            "Could not detect the forge. Pass `--forge`.",
+        ));
+    };
+    let vcs = vcs_or_infer(release_create_args.use_vcs)?;
+    let tag = match release_create_args.tag {
+        Some(tag) => tag,
+        None => current_tag(vcs)?,
+    };
+    let notes = release_notes(vcs, &tag, release_create_args.notes_from)?;
+    // Matches the semver convention that a version with a hyphen suffix (e.g. `v1.2.3-dev`) is a pre-release.
+    let is_prerelease = tag.contains('-');
+
+    eprintln!("Creating {} release for tag: {}", forge, tag);
+    let command = match forge {
+        VCSForge::GitHub => {
+            let mut gh_command = PrintableShellCommand::new("gh");
+            gh_command.args(["release", "create", &tag]);
+            gh_command.args(["--title", &tag]);
+            gh_command.args(["--notes", &notes]);
+            gh_command.arg("--verify-tag");
+            if release_create_args.draft {
+                gh_command.arg("--draft");
+            }
+            if is_prerelease {
+                gh_command.arg("--prerelease");
+            }
+            if !release_create_args.artifacts.is_empty() {
+                gh_command.arg("--");
+                gh_command.arg_each(&release_create_args.artifacts);
+            }
+            gh_command
+        }
+        VCSForge::GitLab => {
+            if release_create_args.draft {
+                return Err(RepoError::user_error(
+                    "GitLab does not support draft releases.",
+                ));
+            }
+            let mut glab_command = PrintableShellCommand::new("glab");
+            glab_command.args(["release", "create", &tag]);
+            glab_command.arg_each(&release_create_args.artifacts);
+            glab_command.args(["--name", &tag]);
+            glab_command.args(["--notes", &notes]);
+            glab_command
+        }
+        VCSForge::Codeberg | VCSForge::Forgejo => {
+            let mut tea_command = PrintableShellCommand::new("tea");
+            tea_command.args(["releases", "create"]);
+            tea_command.args(["--tag", &tag]);
+            tea_command.args(["--title", &tag]);
+            tea_command.args(["--note", &notes]);
+            if release_create_args.draft {
+                tea_command.arg("--draft");
+            }
+            if is_prerelease {
+                tea_command.arg("--prerelease");
+            }
+            for artifact in &release_create_args.artifacts {
+                tea_command.arg("--asset");
+                tea_command.arg(artifact);
+            }
+            tea_command
+        }
+    };
+    command_must_succeed_interactively(command)
+}
+
+pub(crate) fn release_command(release_args: ReleaseArgs) -> RepoResult<()> {
+    match release_args.command {
+        ReleaseCommand::Create(release_create_args) => release_create(release_create_args),
+    }
+}
@@ -4,9 +4,11 @@ use clap::{Args, Subcommand};
 use printable_shell_command::PrintableShellCommand;
 
 use crate::common::{
+    command::command_must_succeed_interactively,
     debug::DebugPrintable,
     ecosystem::Ecosystem,
-    package_manager::{PackageManager, PackageManagerArgs, PACKAGE_LOCK_JSON_PATH},
+    error::RepoResult,
+    package_manager::{PackageManager, PackageManagerArgs, DENO_LOCK_PATH, PACKAGE_LOCK_JSON_PATH},
 };
 
 #[derive(Args, Debug)]
@@ -23,9 +25,9 @@ enum SetupCommand {
 }
 
 // TODO: skip empty deps?
-fn npm_install() {
+fn npm_install() -> RepoResult<()> {
     println!("Installing dependencies using: `npm`");
-    let install_commmand = match exists(PACKAGE_LOCK_JSON_PATH).unwrap() {
+    let install_commmand = match exists(PACKAGE_LOCK_JSON_PATH)? {
         true => "ci",
         false => {
             println!(
@@ -35,92 +37,122 @@ fn npm_install() {
             "install"
         }
     };
-    PrintableShellCommand::new("npm")
-        .arg_each([install_commmand])
-        .debug_print()
-        .status()
-        .expect("Could not install dependencies using `npm`");
+    let mut command = PrintableShellCommand::new("npm");
+    command.arg_each([install_commmand]);
+    command_must_succeed_interactively(command)
 }
 
-fn bun_install() {
+fn bun_install() -> RepoResult<()> {
     println!("Installing dependencies using: `bun`");
-    PrintableShellCommand::new("bun")
-        .arg_each(["install", "--frozen-lockfile"])
-        .debug_print()
-        .status()
-        .expect("Could not install dependencies using `bun`");
+    let mut command = PrintableShellCommand::new("bun");
+    command.arg_each(["install", "--frozen-lockfile"]);
+    command_must_succeed_interactively(command)
 }
 
-fn yarn_install() {
+fn yarn_install() -> RepoResult<()> {
     println!("Installing dependencies using: `npx yarn`");
-    PrintableShellCommand::new("npx")
-        .arg_each(["yarn", "install", "--frozen-lockfile"])
-        .debug_print()
-        .status()
-        .expect("Could not install dependencies using `npx yarn`");
+    let mut command = PrintableShellCommand::new("npx");
+    command.arg_each(["yarn", "install", "--frozen-lockfile"]);
+    command_must_succeed_interactively(command)
 }
 
-fn pnpm_install() {
+fn pnpm_install() -> RepoResult<()> {
     println!("Installing dependencies using: `npx pnpm`");
-    PrintableShellCommand::new("npx")
-        .arg_each(["pnpm", "install", "--frozen-lockfile"])
-        .debug_print()
-        .status()
-        .expect("Could not install dependencies using `npx pnpm`");
+    let mut command = PrintableShellCommand::new("npx");
+    command.arg_each(["pnpm", "install", "--frozen-lockfile"]);
+    command_must_succeed_interactively(command)
+}
+
+fn deno_install() -> RepoResult<()> {
+    println!("Installing dependencies using: `deno`");
+    let mut command = PrintableShellCommand::new("deno");
+    command.arg("install");
+    if exists(DENO_LOCK_PATH)? {
+        command.arg("--frozen");
+    }
+    command_must_succeed_interactively(command)
 }
 
-fn cargo_install() {
+fn bundler_install() -> RepoResult<()> {
+    println!("Installing dependencies using: `bundle`");
+    let mut command = PrintableShellCommand::new("bundle");
+    command.arg_each(["install", "--frozen"]);
+    command_must_succeed_interactively(command)
+}
+
+fn cargo_install() -> RepoResult<()> {
     println!("Installing dependencies using: `cargo`");
     println!("Installing dependencies by building the default target. For more information, see: https://github.com/rust-lang/cargo/issues/2644");
     // TODO: https://github.com/rust-lang/cargo/issues/2644
-    PrintableShellCommand::new("cargo")
-        .arg("build")
-        .debug_print()
-        .status()
-        .expect("Could not install dependencies using `cargo`");
+    let mut command = PrintableShellCommand::new("cargo");
+    command.arg("build");
+    command_must_succeed_interactively(command)
+}
+
+/// Installs the dependencies of `ecosystem` using its detected package manager.
+fn install_ecosystem_dependencies(
+    ecosystem: Ecosystem,
+    package_manager: &PackageManager,
+) -> RepoResult<()> {
+    // TODO: encode this in the type system
+    match (ecosystem, package_manager) {
+        (Ecosystem::JavaScript, PackageManager::Npm) => npm_install(),
+        (Ecosystem::JavaScript, PackageManager::Bun) => bun_install(),
+        (Ecosystem::JavaScript, PackageManager::Yarn) => yarn_install(),
+        (Ecosystem::JavaScript, PackageManager::Pnpm) => pnpm_install(),
+        (Ecosystem::Jsr, PackageManager::Deno) => deno_install(),
+        (Ecosystem::Rust, PackageManager::Cargo) => cargo_install(),
+        (Ecosystem::Ruby, PackageManager::Bundler) => bundler_install(),
+        (ecosystem, package_manager) => {
+            Err(package_manager
+                .unsupported_error(&format!("installing `{}` dependencies", ecosystem)))
+        }
+    }
 }
 
 // TODO: multiple package managers in a single repo
-fn setup_dependencies(package_manager_args: PackageManagerArgs) {
+fn setup_dependencies(package_manager_args: PackageManagerArgs) -> RepoResult<()> {
     // TODO: multiple ecosystems
     let package_manager = package_manager_args.package_manager;
     match package_manager {
-        Some(PackageManager::Npm) => npm_install(),
-        Some(PackageManager::Bun) => bun_install(),
-        Some(PackageManager::Yarn) => yarn_install(),
-        Some(PackageManager::Pnpm) => pnpm_install(),
-        Some(PackageManager::Cargo) => cargo_install(),
+        Some(PackageManager::Npm) => npm_install()?,
+        Some(PackageManager::Bun) => bun_install()?,
+        Some(PackageManager::Yarn) => yarn_install()?,
+        Some(PackageManager::Pnpm) => pnpm_install()?,
+        Some(PackageManager::Deno) => deno_install()?,
+        Some(PackageManager::Cargo) => cargo_install()?,
+        Some(PackageManager::Bundler) => bundler_install()?,
         None => {
-            if let Some(package_manager) =
+            let javascript_package_manager =
                 PackageManager::auto_detect_preferred_package_manager_for_ecosystem(
                     Ecosystem::JavaScript,
-                )
-            {
-                // TODO: encode this in the type system
-                match package_manager {
-                    PackageManager::Npm => npm_install(),
-                    PackageManager::Bun => bun_install(),
-                    PackageManager::Yarn => yarn_install(),
-                    PackageManager::Pnpm => pnpm_install(),
-                    PackageManager::Cargo => panic!("unrechachable"),
+                );
+            if let Some(package_manager) = &javascript_package_manager {
+                install_ecosystem_dependencies(Ecosystem::JavaScript, package_manager)?;
+            }
+            // `deno install` also installs `package.json` dependencies, so we only run it if there is no other JS package manager.
+            if javascript_package_manager.is_none() {
+                if let Some(package_manager) =
+                    PackageManager::auto_detect_preferred_package_manager_for_ecosystem(
+                        Ecosystem::Jsr,
+                    )
+                {
+                    install_ecosystem_dependencies(Ecosystem::Jsr, &package_manager)?;
                 }
             }
-            if let Some(package_manager) =
-                PackageManager::auto_detect_preferred_package_manager_for_ecosystem(Ecosystem::Rust)
-            {
-                // TODO: encode this in the type system
-                match package_manager {
-                    PackageManager::Npm => panic!("unrechachable"),
-                    PackageManager::Bun => panic!("unrechachable"),
-                    PackageManager::Yarn => panic!("unrechachable"),
-                    PackageManager::Pnpm => panic!("unrechachable"),
-                    PackageManager::Cargo => cargo_install(),
+            for ecosystem in [Ecosystem::Rust, Ecosystem::Ruby] {
+                if let Some(package_manager) =
+                    PackageManager::auto_detect_preferred_package_manager_for_ecosystem(ecosystem)
+                {
+                    install_ecosystem_dependencies(ecosystem, &package_manager)?;
                 }
             }
         }
     }
+    Ok(())
 }
 
+// Treats a missing `make` binary the same as a missing `setup` target.
 pub(crate) fn make_setup_exists() -> bool {
     PrintableShellCommand::new("make")
         .arg_each(["-n", "setup"])
@@ -128,32 +160,55 @@ pub(crate) fn make_setup_exists() -> bool {
         .stdout(Stdio::null())
         .stderr(Stdio::null())
         .status()
-        .expect("Could not detect whether `make setup` exists")
-        .success()
+        .map(|exit_status| exit_status.success())
+        .unwrap_or(false)
 }
 
-pub(crate) fn make_setup() {
-    PrintableShellCommand::new("make")
-        .arg("setup")
-        .debug_print()
-        .status()
-        .expect("Could not run `make setup` exists`");
+pub(crate) fn make_setup() -> RepoResult<()> {
+    let mut command = PrintableShellCommand::new("make");
+    command.arg("setup");
+    command_must_succeed_interactively(command)
 }
 
-pub(crate) fn setup_command(setup_args: SetupArgs) {
+/// Runs `make setup` if available, else installs dependencies for all detected ecosystems.
+pub(crate) fn setup_inferred() -> RepoResult<()> {
+    if make_setup_exists() {
+        eprintln!("Running: make setup");
+        make_setup()
+    } else {
+        setup_dependencies(PackageManagerArgs {
+            package_manager: None,
+        })
+    }
+}
+
+pub(crate) fn setup_command(setup_args: SetupArgs) -> RepoResult<()> {
     match setup_args.command {
-        None => {
-            if make_setup_exists() {
-                eprintln!("Running: make setup");
-                make_setup();
-            } else {
-                setup_dependencies(PackageManagerArgs {
-                    package_manager: None,
-                });
-            }
-        }
+        None => setup_inferred(),
         Some(SetupCommand::Dependencies(package_manager_args)) => {
-            setup_dependencies(package_manager_args);
+            setup_dependencies(package_manager_args)
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        commands::setup::install_ecosystem_dependencies,
+        common::{ecosystem::Ecosystem, error::RepoErrorKind, package_manager::PackageManager},
     };
+
+    #[test]
+    fn test_unsupported_package_manager_for_ecosystem() {
+        for (ecosystem, package_manager) in [
+            (Ecosystem::JavaScript, PackageManager::Cargo),
+            (Ecosystem::Jsr, PackageManager::Npm),
+            (Ecosystem::Rust, PackageManager::Bundler),
+            (Ecosystem::Ruby, PackageManager::Deno),
+        ] {
+            let error = install_ecosystem_dependencies(ecosystem, &package_manager).unwrap_err();
+            assert_eq!(error.kind, RepoErrorKind::UserError);
+            assert!(error.message.contains("is not supported for installing"));
+        }
+    }
 }
@@ -4,7 +4,8 @@ use clap::{Args, Subcommand};
 use printable_shell_command::PrintableShellCommand;
 
 use crate::common::{
-    inference::get_stdout,
+    command::command_must_get_stdout,
+    error::{RepoError, RepoResult},
     vcs::{auto_detect_preferred_vcs_and_repo_root, VcsKind},
 };
 
@@ -40,7 +41,7 @@ enum LatestCommitSubcommand {
     Hash,
 }
 
-fn jj_get_latest_commmit_hash() -> Result<String, String> {
+fn jj_get_latest_commmit_hash() -> RepoResult<String> {
     let mut jj_command = PrintableShellCommand::new("jj");
     jj_command.args([
         "--no-graph",
@@ -53,47 +54,44 @@ fn jj_get_latest_commmit_hash() -> Result<String, String> {
         "::@ & ((~description(exact:\"\") & ~empty()) | merges())",
     ]);
     jj_command.args(["--template", "commit_id"]);
-    if let Some(hash) = get_stdout(jj_command) {
-        return Ok(hash.trim().to_owned());
-    }
-
-    Err("Could not get latest hash from `jj`.".to_owned())
+    let hash = command_must_get_stdout(jj_command)?;
+    Ok(hash.trim().to_owned())
 }
 
-fn git_get_latest_commmit_hash() -> Result<String, String> {
+fn git_get_latest_commmit_hash() -> RepoResult<String> {
     let mut git_command = PrintableShellCommand::new("git");
     git_command.args(["rev-parse", "HEAD"]);
-    if let Some(hash) = get_stdout(git_command) {
-        return Ok(hash.trim().to_owned());
-    }
-
-    Err("Could not get latest hash from `git`.".to_owned())
+    let hash = command_must_get_stdout(git_command)?;
+    Ok(hash.trim().to_owned())
 }
 
-pub(crate) fn vcs_command(vcs_args: VcsArgs) -> Result<(), String> {
+pub(crate) fn vcs_command(vcs_args: VcsArgs) -> RepoResult<()> {
+    let current_dir = current_dir()?;
     match vcs_args.command {
-        VcsCommand::Kind => {
-            match auto_detect_preferred_vcs_and_repo_root(&current_dir().unwrap()) {
-                Some((vcs, _)) => print!("{}", vcs),
-                None => return Err("Could not detect a VCS repo.".to_owned()),
-            }
-        }
-        VcsCommand::Root => {
-            match auto_detect_preferred_vcs_and_repo_root(&current_dir().unwrap()) {
-                Some((_, path)) => print!("{}", path),
-                None => return Err("Could not detect a VCS repo.".to_owned()),
-            }
-        }
+        VcsCommand::Kind => match auto_detect_preferred_vcs_and_repo_root(&current_dir) {
+            Some((vcs, _)) => print!("{}", vcs),
+            None => return Err(RepoError::detection_failure("Could not detect a VCS repo.")),
+        },
+        VcsCommand::Root => match auto_detect_preferred_vcs_and_repo_root(&current_dir) {
+            Some((_, path)) => print!("{}", path),
+            None => return Err(RepoError::detection_failure("Could not detect a VCS repo.")),
+        },
         VcsCommand::LatestCommit(latest_commit_args) => {
             match latest_commit_args.command {
                 LatestCommitSubcommand::Hash => {
-                    match auto_detect_preferred_vcs_and_repo_root(&current_dir().unwrap()) {
+                    match auto_detect_preferred_vcs_and_repo_root(&current_dir) {
                         Some((VcsKind::Jj, _)) => print!("{}", jj_get_latest_commmit_hash()?),
                         Some((VcsKind::Git, _)) => print!("{}", git_get_latest_commmit_hash()?),
                         Some((VcsKind::Mercurial, _)) => {
-                            return Err("Mercurial is unsupported for this operation.".into());
+                            return Err(RepoError::user_error(
+                                "Mercurial is unsupported for this operation.",
+                            ));
+                        }
+                        None => {
+                            return Err(RepoError::detection_failure(
+                                "Could not detect a VCS repo.",
+                            ))
                         }
-                        None => return Err("Could not detect a VCS repo.".to_owned()),
                     }
                     // dbg!(latest_commit_args);
                 }
@@ -1,19 +1,25 @@
-use std::fs::File;
+use std::fmt::Display;
+use std::fs::{exists, read_dir, read_to_string, write, File};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
-use std::{fmt::Display, process::exit};
 
 use cargo_metadata::semver::Prerelease;
 use clap::{Args, Subcommand, ValueEnum};
 
 use cargo_metadata::{semver::Version, MetadataCommand};
 use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
 use schemars::{schema_for, JsonSchema, Schema, SchemaGenerator};
 use serde::{Deserialize, Serialize};
 
+use crate::common::command::{command_must_get_stdout, command_must_succeed_interactively};
 use crate::common::commit_wrapped_operation::CommitWrappedOperation;
 use crate::common::config::Config;
 use crate::common::debug::DebugPrintable;
-use crate::common::inference::get_stdout;
+use crate::common::error::{RepoError, RepoResult};
+use crate::common::jsonc::{set_top_level_string_value, strip_jsonc};
+use crate::common::package_manager::{DENO_JSONC_PATH, DENO_JSON_PATH, JSR_JSON_PATH};
 use crate::common::vcs::{vcs_or_infer, VcsKind};
 use crate::common::{
     ecosystem::{Ecosystem, EcosystemArgs},
@@ -199,32 +205,201 @@ struct PackageJSONWithVersion {
     version: Option<String>,
 }
 
-pub(crate) fn npm_get_version() -> Result<String, String> {
+pub(crate) fn npm_get_version() -> RepoResult<String> {
     // TODO: use `npm root`
     // TODO: semantically parse version
     let Ok(file) = File::open(PACKAGE_JSON_PATH) else {
-        return Err("Could not file `package.json`".to_owned());
+        return Err(RepoError::detection_failure(
+            "Could not find `package.json`",
+        ));
     };
     let Ok(package_json) = serde_json::from_reader::<_, PackageJSONWithVersion>(file) else {
-        return Err("Could not read `package.json`".to_owned());
+        return Err(RepoError::file_system("Could not read `package.json`"));
     };
     match package_json.version {
         Some(version) => Ok(match version.strip_prefix("v") {
             Some(version) => version.to_owned(),
             None => version,
         }),
-        None => Err("No version field found in `package.json`".to_owned()),
+        None => Err(RepoError::detection_failure(
+            "No version field found in `package.json`",
+        )),
     }
 }
 
-pub(crate) fn cargo_get_version() -> Result<String, String> {
+/// Returns the path of the first JSR manifest (`deno.json`, `deno.jsonc`, or `jsr.json`) that exists.
+pub(crate) fn jsr_manifest_path() -> Option<&'static str> {
+    [DENO_JSON_PATH, DENO_JSONC_PATH, JSR_JSON_PATH]
+        .into_iter()
+        .find(|path| exists(path).unwrap_or(false))
+}
+
+pub(crate) fn jsr_get_version() -> RepoResult<String> {
+    let Some(manifest_path) = jsr_manifest_path() else {
+        return Err(RepoError::detection_failure(
+            "Could not find `deno.json`, `deno.jsonc`, or `jsr.json`",
+        ));
+    };
+    let contents = read_to_string(manifest_path)?;
+    let manifest = serde_json::from_str::<PackageJSONWithVersion>(&strip_jsonc(&contents))
+        .map_err(|e| {
+            RepoError::user_error(format!("`{}` is not valid JSON: {}", manifest_path, e))
+        })?;
+    match manifest.version {
+        Some(version) => Ok(match version.strip_prefix("v") {
+            Some(version) => version.to_owned(),
+            None => version,
+        }),
+        None => Err(RepoError::detection_failure(format!(
+            "No version field found in `{}`",
+            manifest_path
+        ))),
+    }
+}
+
+/// Returns the first `*.gemspec` file in the current folder (sorted by name).
+pub(crate) fn gemspec_path() -> RepoResult<PathBuf> {
+    let mut gemspec_paths: Vec<PathBuf> = read_dir(".")?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "gemspec")
+        })
+        .collect();
+    gemspec_paths.sort();
+    gemspec_paths
+        .into_iter()
+        .next()
+        .ok_or_else(|| RepoError::detection_failure("Could not find a `.gemspec` file"))
+}
+
+fn find_version_rb_files(dir: &Path, found: &mut Vec<PathBuf>) -> RepoResult<()> {
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            find_version_rb_files(&path, found)?;
+        } else if path.file_name().is_some_and(|name| name == "version.rb") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The `version.rb` paths that a `.gemspec` may load, in order of preference (e.g. `require_relative "lib/foo/version"` or `require "foo/version"`).
+fn gemspec_version_file_candidates(gemspec: &str) -> Vec<PathBuf> {
+    let mut candidates = vec![];
+    let require_relative = static_regex!(r#"require_relative\(?\s*["']([^"']*version)(\.rb)?["']"#);
+    if let Some(captures) = require_relative.captures(gemspec) {
+        candidates.push(PathBuf::from(format!("{}.rb", &captures[1])));
+    }
+    let require = static_regex!(r#"require\(?\s*["']([^"']*/version)(\.rb)?["']"#);
+    if let Some(captures) = require.captures(gemspec) {
+        candidates.push(PathBuf::from(format!("lib/{}.rb", &captures[1])));
+    }
+    candidates
+}
+
+/// Returns the `version.rb` file referenced by the `.gemspec` (e.g. `require_relative "lib/foo/version"`), falling back to the sole `lib/**/version.rb`.
+fn ruby_version_file_path() -> RepoResult<PathBuf> {
+    let gemspec = read_to_string(gemspec_path()?)?;
+    for path in gemspec_version_file_candidates(&gemspec) {
+        if exists(&path)? {
+            return Ok(path);
+        }
+    }
+
+    let mut found = vec![];
+    if exists("lib")? {
+        find_version_rb_files(Path::new("lib"), &mut found)?;
+    }
+    match found.len() {
+        1 => Ok(found.remove(0)),
+        0 => Err(RepoError::detection_failure(
+            "Could not find `lib/**/version.rb`",
+        )),
+        _ => Err(RepoError::detection_failure(
+            "Found multiple `lib/**/version.rb` files, and the `.gemspec` does not reference one of them.",
+        )),
+    }
+}
+
+/// RubyGems marks pre-releases with letters after a `.` rather than a `-` (e.g. `1.2.3.dev.4` for `1.2.3-dev.4`).
+fn rubygems_version(version: &Version) -> String {
+    let mut rubygems_version = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        rubygems_version.push('.');
+        rubygems_version.push_str(version.pre.as_str());
+    }
+    rubygems_version
+}
+
+/// The inverse of `rubygems_version(…)`, so that the result can be parsed (and bumped) as semver.
+fn semver_from_rubygems_version(rubygems_version: &str) -> String {
+    let pre_release = static_regex!(r"^(\d+\.\d+\.\d+)\.([0-9A-Za-z.-]*[A-Za-z][0-9A-Za-z.-]*)$");
+    match pre_release.captures(rubygems_version) {
+        Some(captures) => format!("{}-{}", &captures[1], &captures[2]),
+        None => rubygems_version.to_owned(),
+    }
+}
+
+fn version_rb_get_version(contents: &str) -> Option<String> {
+    let version_constant = static_regex!(r#"VERSION\s*=\s*["']([^"']+)["']"#);
+    let captures = version_constant.captures(contents)?;
+    Some(semver_from_rubygems_version(&captures[1]))
+}
+
+fn version_rb_set_version(contents: &str, version: &Version) -> Option<String> {
+    let version_constant = static_regex!(r#"(VERSION\s*=\s*["'])([^"']+)(["'])"#);
+    if !version_constant.is_match(contents) {
+        return None;
+    }
+    Some(
+        version_constant
+            .replace(
+                contents,
+                format!("${{1}}{}${{3}}", rubygems_version(version)).as_str(),
+            )
+            .into_owned(),
+    )
+}
+
+pub(crate) fn ruby_get_version() -> RepoResult<String> {
+    let version_file_path = ruby_version_file_path()?;
+    let contents = read_to_string(&version_file_path)?;
+    version_rb_get_version(&contents).ok_or_else(|| {
+        RepoError::detection_failure(format!(
+            "No `VERSION` constant found in: {}",
+            version_file_path.to_string_lossy()
+        ))
+    })
+}
+
+fn ruby_set_version(version: &Version) -> RepoResult<()> {
+    let version_file_path = ruby_version_file_path()?;
+    let contents = read_to_string(&version_file_path)?;
+    let Some(contents) = version_rb_set_version(&contents, version) else {
+        return Err(RepoError::detection_failure(format!(
+            "No `VERSION` constant found in: {}",
+            version_file_path.to_string_lossy()
+        )));
+    };
+    write(&version_file_path, contents)?;
+    Ok(())
+}
+
+fn ruby_bump_version(version_bump_magnitude: &VersionBumpMagnitude) -> RepoResult<()> {
+    let version = parse_current_version(&ruby_get_version()?)?;
+    ruby_set_version(&bump_version(version, version_bump_magnitude))
+}
+
+pub(crate) fn cargo_get_version() -> RepoResult<String> {
     let mut command = MetadataCommand::new();
     let Ok(metadata) = command
         .manifest_path("./Cargo.toml")
         .current_dir(".")
         .exec()
     else {
-        return Err("Could not file `Cargo.toml`".to_owned());
+        return Err(RepoError::detection_failure("Could not find `Cargo.toml`"));
     };
     if let Some(root_package) = metadata.root_package() {
         // return Err("Could not file `Cargo.toml` root package.".to_owned());
@@ -259,7 +434,7 @@ pub(crate) fn cargo_get_version() -> Result<String, String> {
         return Ok(workspace_package.version.to_string());
     }
 
-    Err("Could not get version.".to_owned())
+    Err(RepoError::detection_failure("Could not get version."))
 }
 
 fn print_version(version: &str, version_get_args: &VersionGetArgs) {
@@ -278,7 +453,7 @@ fn dev_bump(version: Version, bump_component: Option<NumberedVersionComponent>)
         }
         NumberedVersionComponent::Minor => {
             version.minor += 1;
-            version.patch += 0;
+            version.patch = 0;
         }
         NumberedVersionComponent::Patch => {
             version.patch += 1;
@@ -294,48 +469,82 @@ fn remove_prerelease(version: Version) -> Version {
     version
 }
 
-fn npm_bump_version(version_bump_magnitude: &VersionBumpMagnitude) {
+fn parse_current_version(version: &str) -> RepoResult<Version> {
+    Version::parse(version).map_err(|e| {
+        RepoError::detection_failure(format!(
+            "Could not parse current version ({}): {}",
+            version, e
+        ))
+    })
+}
+
+// Matches the semantics of `npm version …`, e.g. bumping the `minor` version of `v1.3.0-dev` results in `v1.3.0`.
+fn bump_version(version: Version, version_bump_magnitude: &VersionBumpMagnitude) -> Version {
+    let mut version = version.clone();
+    let is_prerelease = !version.pre.is_empty();
+    match version_bump_magnitude {
+        VersionBumpMagnitude::Major => {
+            if !(is_prerelease && version.minor == 0 && version.patch == 0) {
+                version.major += 1;
+            }
+            version.minor = 0;
+            version.patch = 0;
+        }
+        VersionBumpMagnitude::Minor => {
+            if !(is_prerelease && version.patch == 0) {
+                version.minor += 1;
+            }
+            version.patch = 0;
+        }
+        VersionBumpMagnitude::Patch => {
+            if !is_prerelease {
+                version.patch += 1;
+            }
+        }
+        VersionBumpMagnitude::Dev(_) => {
+            return dev_bump(version, version_bump_magnitude.bump_component());
+        }
+    }
+    remove_prerelease(version)
+}
+
+fn npm_bump_version(version_bump_magnitude: &VersionBumpMagnitude) -> RepoResult<()> {
     if matches!(version_bump_magnitude, VersionBumpMagnitude::Dev(_)) {
-        let version = npm_get_version().expect("Could not get current version.");
-        let version = Version::parse(&version).expect("Could not parse current version.");
-        npm_set_version(dev_bump(version, version_bump_magnitude.bump_component()));
-        return;
-    }
-    PrintableShellCommand::new("npm")
-        .arg_each([
-            "version",
-            "--no-git-tag-version",
-            &version_bump_magnitude.to_string(),
-        ])
-        .debug_print()
-        .status()
-        .expect("Could not bump version using `npm`");
-}
-
-fn cargo_bump_version(version_bump_magnitude: &VersionBumpMagnitude) {
+        let version = parse_current_version(&npm_get_version()?)?;
+        return npm_set_version(dev_bump(version, version_bump_magnitude.bump_component()));
+    }
+    let mut command = PrintableShellCommand::new("npm");
+    command.arg_each([
+        "version",
+        "--no-git-tag-version",
+        &version_bump_magnitude.to_string(),
+    ]);
+    command_must_succeed_interactively(command)
+}
+
+fn jsr_bump_version(version_bump_magnitude: &VersionBumpMagnitude) -> RepoResult<()> {
+    let version = parse_current_version(&jsr_get_version()?)?;
+    jsr_set_version(&bump_version(version, version_bump_magnitude))
+}
+
+fn cargo_bump_version(version_bump_magnitude: &VersionBumpMagnitude) -> RepoResult<()> {
     if matches!(version_bump_magnitude, VersionBumpMagnitude::Dev(_)) {
-        let version = cargo_get_version().expect("Could not get current version.");
-        let version = Version::parse(&version).expect("Could not parse current version.");
-        cargo_set_version(dev_bump(version, version_bump_magnitude.bump_component()));
-        return;
+        let version = parse_current_version(&cargo_get_version()?)?;
+        return cargo_set_version(dev_bump(version, version_bump_magnitude.bump_component()));
     }
 
     // Match `npm`: Bumping a `patch` of a pre-release removes the pre-release label but keeps the same patch.
     if matches!(version_bump_magnitude, VersionBumpMagnitude::Patch) {
-        let version = cargo_get_version().expect("Could not get current version.");
-        let version = Version::parse(&version).expect("Could not parse current version.");
+        let version = parse_current_version(&cargo_get_version()?)?;
         if !version.pre.is_empty() {
-            cargo_set_version(remove_prerelease(version));
-            return;
+            return cargo_set_version(remove_prerelease(version));
         }
     }
 
     eprintln!("Assuming `cargo-bump` is installed…");
-    PrintableShellCommand::new("cargo")
-        .args(["bump", &version_bump_magnitude.to_string()])
-        .debug_print()
-        .status()
-        .expect("Could not bump version using `cargo-bump`");
+    let mut command = PrintableShellCommand::new("cargo");
+    command.args(["bump", &version_bump_magnitude.to_string()]);
+    command_must_succeed_interactively(command)
 }
 
 pub(crate) fn detect_ecosystem_by_getting_version(
@@ -344,11 +553,19 @@ pub(crate) fn detect_ecosystem_by_getting_version(
     for (ecosystem, get_version) in [
         (
             Ecosystem::JavaScript,
-            npm_get_version as fn() -> Result<String, String>,
+            npm_get_version as fn() -> RepoResult<String>,
+        ),
+        (
+            Ecosystem::Jsr,
+            jsr_get_version as fn() -> RepoResult<String>,
         ),
         (
             Ecosystem::Rust,
-            cargo_get_version as fn() -> Result<String, String>,
+            cargo_get_version as fn() -> RepoResult<String>,
+        ),
+        (
+            Ecosystem::Ruby,
+            ruby_get_version as fn() -> RepoResult<String>,
         ),
     ] {
         if let Some(required_ecosystem) = ecosystem_args.ecosystem {
@@ -366,33 +583,29 @@ pub(crate) fn detect_ecosystem_by_getting_version(
 
 pub(crate) fn must_detect_ecosystem_by_getting_version(
     ecosystem_args: &EcosystemArgs,
-) -> (Ecosystem, String) {
+) -> RepoResult<(Ecosystem, String)> {
     detect_ecosystem_by_getting_version(ecosystem_args)
-        .expect("Could not detect an ecosystem for this repo.")
+        .ok_or_else(|| RepoError::detection_failure("Could not detect an ecosystem for this repo."))
 }
 
-fn version_get_and_print(ecosystem_args: &EcosystemArgs, version_get_args: &VersionGetArgs) {
+fn version_get_and_print(
+    ecosystem_args: &EcosystemArgs,
+    version_get_args: &VersionGetArgs,
+) -> RepoResult<()> {
     let Some((_, version)) = detect_ecosystem_by_getting_version(ecosystem_args) else {
-        eprintln!("No version found.");
-        exit(1);
+        return Err(RepoError::detection_failure("No version found."));
     };
     print_version(&version, version_get_args);
+    Ok(())
 }
 
-fn version_describe_and_print(version_describe_args: &VersionDescribeArgs) {
-    let Ok(vcs) = vcs_or_infer(version_describe_args.use_vcs) else {
-        eprintln!("Could not determine VCS to use.");
-        exit(1);
-    };
+fn version_describe_and_print(version_describe_args: &VersionDescribeArgs) -> RepoResult<()> {
+    let vcs = vcs_or_infer(version_describe_args.use_vcs)?;
     let description = match vcs {
         VcsKind::Git => {
             let mut git_command = PrintableShellCommand::new("git");
             git_command.args(["describe", "--tags"]);
-            let Some(description) = get_stdout(git_command) else {
-                eprintln!("Could not get description using `git`.");
-                exit(1);
-            };
-            description
+            command_must_get_stdout(git_command)?
         }
         VcsKind::Jj => {
             // Based on https://github.com/jj-vcs/jj/discussions/2563#discussioncomment-11885001
@@ -403,19 +616,18 @@ fn version_describe_and_print(version_describe_args: &VersionDescribeArgs) {
                 "--template",
                 "commit_id.short(8) ++ \" \" ++ tags ++ \"\n\"",
             ]);
-            let Some(commits) = get_stdout(jj_command) else {
-                eprintln!("Could not get description using `jj`.");
-                exit(1);
-            };
+            let commits = command_must_get_stdout(jj_command)?;
             let lines: Vec<&str> = commits.split("\n").collect();
             if lines.is_empty() {
-                eprintln!("Could not get enough commits to describe using `jj`.");
-                exit(1);
+                return Err(RepoError::detection_failure(
+                    "Could not get enough commits to describe using `jj`.",
+                ));
             }
             let first_line_parts: Vec<&str> = lines[0].split(" ").collect();
             if first_line_parts.len() < 2 {
-                eprintln!("Could not get tag to describe using `jj`.");
-                exit(1);
+                return Err(RepoError::detection_failure(
+                    "Could not get tag to describe using `jj`.",
+                ));
             }
             let tag = first_line_parts[1];
             if lines.len() == 1 {
@@ -426,45 +638,110 @@ fn version_describe_and_print(version_describe_args: &VersionDescribeArgs) {
             }
         }
         VcsKind::Mercurial => {
-            eprintln!("Mercurial is unsupported for this operation.");
-            exit(1);
+            return Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            ));
         }
     };
     print!("{}", description);
+    Ok(())
 }
 
 // TODO: get version from output of the bump commands themselves?
-// TODO: return `Result<Version, …>`.
+// TODO: return `RepoResult<Version>`.
 fn version_bump(
     ecosystem_args: &EcosystemArgs,
     version_bump_magnitude: &VersionBumpMagnitude,
-) -> Result<String, String> {
+) -> RepoResult<String> {
     let auto_print_version = |repo_ecosystem: Ecosystem| {
         eprintln!("Bumped version using ecosystem: {}", repo_ecosystem);
     };
-    match must_detect_ecosystem_by_getting_version(ecosystem_args) {
+    match must_detect_ecosystem_by_getting_version(ecosystem_args)? {
         (Ecosystem::JavaScript, _) => {
-            npm_bump_version(version_bump_magnitude);
+            npm_bump_version(version_bump_magnitude)?;
             auto_print_version(Ecosystem::JavaScript);
-            npm_get_version()
+            let version = npm_get_version()?;
+            sync_jsr_version(&parse_current_version(&version)?)?;
+            Ok(version)
+        }
+        (Ecosystem::Jsr, _) => {
+            jsr_bump_version(version_bump_magnitude)?;
+            auto_print_version(Ecosystem::Jsr);
+            let version = jsr_get_version()?;
+            sync_npm_version(&parse_current_version(&version)?)?;
+            Ok(version)
         }
         (Ecosystem::Rust, _) => {
-            cargo_bump_version(version_bump_magnitude);
+            cargo_bump_version(version_bump_magnitude)?;
             auto_print_version(Ecosystem::Rust);
             cargo_get_version()
         }
+        (Ecosystem::Ruby, _) => {
+            ruby_bump_version(version_bump_magnitude)?;
+            auto_print_version(Ecosystem::Ruby);
+            ruby_get_version()
+        }
     }
 }
 
-fn npm_set_version(version: Version) {
-    PrintableShellCommand::new("npm")
-        .args(["version", "--no-git-tag-version", &version.to_string()])
-        .debug_print()
-        .status()
-        .expect("Could not bump version using `npm`");
+fn npm_set_version(version: Version) -> RepoResult<()> {
+    let mut command = PrintableShellCommand::new("npm");
+    command.args(["version", "--no-git-tag-version", &version.to_string()]);
+    command_must_succeed_interactively(command)
+}
+
+fn jsr_set_version(version: &Version) -> RepoResult<()> {
+    let Some(manifest_path) = jsr_manifest_path() else {
+        return Err(RepoError::detection_failure(
+            "Could not find `deno.json`, `deno.jsonc`, or `jsr.json`",
+        ));
+    };
+    let contents = read_to_string(manifest_path)?;
+    let Some(contents) = set_top_level_string_value(&contents, "version", &version.to_string())
+    else {
+        return Err(RepoError::detection_failure(format!(
+            "No version field found in `{}`",
+            manifest_path
+        )));
+    };
+    write(manifest_path, contents)?;
+    Ok(())
 }
 
-fn cargo_set_version(version: Version) {
+/// Keeps the JSR manifest version (if any) in sync with `package.json`.
+fn sync_jsr_version(version: &Version) -> RepoResult<()> {
+    let Some(manifest_path) = jsr_manifest_path() else {
+        return Ok(());
+    };
+    sync_jsr_manifest_version(Path::new(manifest_path), version)
+}
+
+/// Many Deno projects don't publish to JSR and have no `version` field, so there is nothing to sync.
+fn sync_jsr_manifest_version(manifest_path: &Path, version: &Version) -> RepoResult<()> {
+    let contents = read_to_string(manifest_path)?;
+    let Some(contents) = set_top_level_string_value(&contents, "version", &version.to_string())
+    else {
+        eprintln!(
+            "No version field found in `{}` (not syncing).",
+            manifest_path.to_string_lossy()
+        );
+        return Ok(());
+    };
+    eprintln!("Syncing version to `{}`.", manifest_path.to_string_lossy());
+    write(manifest_path, contents)?;
+    Ok(())
+}
+
+/// Keeps the `package.json` version (if any) in sync with the JSR manifest.
+fn sync_npm_version(version: &Version) -> RepoResult<()> {
+    if !exists(PACKAGE_JSON_PATH)? {
+        return Ok(());
+    }
+    eprintln!("Syncing version to `package.json`.");
+    npm_set_version(version.clone())
+}
+
+fn cargo_set_version(version: Version) -> RepoResult<()> {
     eprintln!("Assuming `cargo-bump` or `cargo-workspaces` is installed…");
     if matches!(
         PrintableShellCommand::new("cargo")
@@ -474,7 +751,7 @@ fn cargo_set_version(version: Version) {
             .map(|status| status.success()),
         Ok(true)
     ) {
-        return;
+        return Ok(());
     };
     if matches!(
         PrintableShellCommand::new("cargo")
@@ -493,21 +770,27 @@ fn cargo_set_version(version: Version) {
             .map(|status| status.success()),
         Ok(true)
     ) {
-        return;
+        return Ok(());
     };
-    panic!("Could not use `cargo-bump` or `cargo-workspaces` to set the version.");
+    Err(RepoError::tool_missing(
+        "Could not use `cargo-bump` or `cargo-workspaces` to set the version. Is one of them installed?",
+    ))
 }
 
-fn version_set(ecosystem_args: &EcosystemArgs, version: Version) {
+fn version_set(ecosystem_args: &EcosystemArgs, version: Version) -> RepoResult<()> {
     eprintln!("Setting version to: v{}", version);
 
-    match must_detect_ecosystem_by_getting_version(ecosystem_args) {
+    match must_detect_ecosystem_by_getting_version(ecosystem_args)? {
         (Ecosystem::JavaScript, _) => {
-            npm_set_version(version);
+            npm_set_version(version.clone())?;
+            sync_jsr_version(&version)
         }
-        (Ecosystem::Rust, _) => {
-            cargo_set_version(version);
+        (Ecosystem::Jsr, _) => {
+            jsr_set_version(&version)?;
+            sync_npm_version(&version)
         }
+        (Ecosystem::Rust, _) => cargo_set_version(version),
+        (Ecosystem::Ruby, _) => ruby_set_version(&version),
     }
 }
 
@@ -528,78 +811,227 @@ fn json_schema_serialize_version(_: &mut SchemaGenerator) -> Schema {
 }
 
 const POST_VERSION: &str = "postVersion";
-fn post_version_change(info: PostVersionInfo) {
-    let config = Config::get();
+fn post_version_change(info: PostVersionInfo) -> RepoResult<()> {
+    let config = Config::get()?;
     let Some(post_version_command) = config.scripts.get(POST_VERSION) else {
-        return;
+        return Ok(());
     };
     let Some((command, args)) = post_version_command.split_first() else {
-        panic!("Command is an empty list: {}", POST_VERSION);
+        return Err(RepoError::config(format!(
+            "Command is an empty list: {}",
+            POST_VERSION
+        )));
     };
 
     let mut subprocess = Command::new(command)
         .args(args)
         .stdin(Stdio::piped())
         .spawn()
-        .unwrap();
-    serde_json::to_writer(subprocess.stdin.as_mut().unwrap(), &info).unwrap();
-    assert!(subprocess.wait().unwrap().success());
+        .map_err(|e| {
+            if e.kind() == ErrorKind::NotFound {
+                RepoError::tool_missing(format!(
+                    "Could not find `{}` script: {}",
+                    POST_VERSION, command
+                ))
+            } else {
+                RepoError::tool_failed(format!(
+                    "Could not run `{}` script ({}): {}",
+                    POST_VERSION, command, e
+                ))
+            }
+        })?;
+    serde_json::to_writer(subprocess.stdin.as_mut().unwrap(), &info)
+        .map_err(|e| RepoError::internal(e.to_string()))?;
+    let exit_status = subprocess.wait()?;
+    if !exit_status.success() {
+        return Err(RepoError::tool_failed(format!(
+            "`{}` script failed ({}): {}",
+            POST_VERSION, exit_status, command
+        )));
+    }
+    Ok(())
 }
 
 // TODO: use traits to abstract across ecosystems
 // TODO: support cross-checking versions across ecosystems
-pub(crate) fn version_command(version_args: VersionArgs) {
+pub(crate) fn version_command(version_args: VersionArgs) -> RepoResult<()> {
     let command = (&version_args.command).into();
     match &version_args.command {
         VersionCommand::Get(version_get_args) => {
-            version_get_and_print(&version_args.ecosystem_args, version_get_args);
+            version_get_and_print(&version_args.ecosystem_args, version_get_args)
         }
         VersionCommand::Describe(version_describe_args) => {
-            version_describe_and_print(version_describe_args);
+            version_describe_and_print(version_describe_args)
         }
         VersionCommand::Set(version_set_args) => {
+            let version = version_set_args
+                .version
+                .strip_prefix("v")
+                .unwrap_or(&version_set_args.version);
+            let version = Version::parse(version).map_err(|e| {
+                RepoError::user_error(format!("Invalid version specified ({}): {}", version, e))
+            })?;
             let commit_wrapped_operation =
-                CommitWrappedOperation::try_from(&version_set_args.commit_args).unwrap();
-            commit_wrapped_operation
-                .perform_operation(&|| {
-                    let version = version_set_args
-                        .version
-                        .strip_prefix("v")
-                        .unwrap_or(&version_set_args.version);
-                    let version = Version::parse(version).expect("Invalid version specified");
-                    version_set(&version_args.ecosystem_args, version.clone());
-                    post_version_change(PostVersionInfo {
-                        command,
-                        magnitude: None,
-                        version: version.clone(),
-                    });
-                    Ok(format!("Set version to: `v{}`", version))
-                })
-                .unwrap();
+                CommitWrappedOperation::try_from(&version_set_args.commit_args)?;
+            commit_wrapped_operation.perform_operation(&|| {
+                version_set(&version_args.ecosystem_args, version.clone())?;
+                post_version_change(PostVersionInfo {
+                    command,
+                    magnitude: None,
+                    version: version.clone(),
+                })?;
+                Ok(format!("Set version to: `v{}`", version))
+            })
         }
         VersionCommand::Bump(version_bump_args) => {
-            Config::get();
+            Config::get()?;
             let commit_wrapped_operation =
-                CommitWrappedOperation::try_from(&version_bump_args.commit_args).unwrap();
-            commit_wrapped_operation
-                .perform_operation(&|| {
-                    let version_bump_magnitude: &VersionBumpMagnitude =
-                        &version_bump_args.magnitude_subcommand;
-                    let new_version =
-                        version_bump(&version_args.ecosystem_args, version_bump_magnitude).unwrap();
-                    let new_version =
-                        Version::parse(&new_version).expect("Internal error: Invalid new version");
-                    post_version_change(PostVersionInfo {
-                        command,
-                        magnitude: Some(version_bump_magnitude.into()),
-                        version: new_version.clone(),
-                    });
-                    Ok(format!(
-                        "Bump to next {} version: `v{}`",
-                        version_bump_magnitude, new_version
-                    ))
-                })
-                .unwrap();
+                CommitWrappedOperation::try_from(&version_bump_args.commit_args)?;
+            commit_wrapped_operation.perform_operation(&|| {
+                let version_bump_magnitude: &VersionBumpMagnitude =
+                    &version_bump_args.magnitude_subcommand;
+                let new_version =
+                    version_bump(&version_args.ecosystem_args, version_bump_magnitude)?;
+                let new_version = Version::parse(&new_version).map_err(|e| {
+                    RepoError::internal(format!("Invalid new version ({}): {}", new_version, e))
+                })?;
+                post_version_change(PostVersionInfo {
+                    command,
+                    magnitude: Some(version_bump_magnitude.into()),
+                    version: new_version.clone(),
+                })?;
+                Ok(format!(
+                    "Bump to next {} version: `v{}`",
+                    version_bump_magnitude, new_version
+                ))
+            })
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{read_to_string, remove_file, write},
+        path::PathBuf,
+    };
+
+    use cargo_metadata::semver::Version;
+
+    use crate::commands::version::{
+        bump_version, gemspec_version_file_candidates, sync_jsr_manifest_version,
+        version_rb_get_version, version_rb_set_version, NumberedVersionComponent,
+        VersionBumpDevArgs, VersionBumpMagnitude,
     };
+
+    fn bumped(version: &str, version_bump_magnitude: VersionBumpMagnitude) -> String {
+        bump_version(Version::parse(version).unwrap(), &version_bump_magnitude).to_string()
+    }
+
+    #[test]
+    fn test_bump_version() {
+        assert_eq!(bumped("1.2.3", VersionBumpMagnitude::Major), "2.0.0");
+        assert_eq!(bumped("1.2.3", VersionBumpMagnitude::Minor), "1.3.0");
+        assert_eq!(bumped("1.2.3", VersionBumpMagnitude::Patch), "1.2.4");
+        // Like `npm version`, bumping a pre-release releases it if possible.
+        assert_eq!(bumped("2.0.0-dev", VersionBumpMagnitude::Major), "2.0.0");
+        assert_eq!(bumped("1.3.0-dev", VersionBumpMagnitude::Minor), "1.3.0");
+        assert_eq!(bumped("1.3.1-dev", VersionBumpMagnitude::Minor), "1.4.0");
+        assert_eq!(bumped("1.2.4-dev", VersionBumpMagnitude::Patch), "1.2.4");
+        let dev = |bump_component| VersionBumpMagnitude::Dev(VersionBumpDevArgs { bump_component });
+        assert_eq!(bumped("1.2.3", dev(None)), "1.2.4-dev");
+        assert_eq!(
+            bumped("1.2.3", dev(Some(NumberedVersionComponent::Minor))),
+            "1.3.0-dev"
+        );
+        assert_eq!(
+            bumped("1.2.3", dev(Some(NumberedVersionComponent::Major))),
+            "2.0.0-dev"
+        );
+    }
+
+    #[test]
+    fn test_sync_jsr_manifest_version() {
+        let manifest_path =
+            env::temp_dir().join(format!("repo-test-deno-{}.json", std::process::id()));
+        let version = Version::parse("1.2.4").unwrap();
+
+        let with_version = "{\n  \"name\": \"@scope/pkg\",\n  \"version\": \"1.2.3\"\n}\n";
+        write(&manifest_path, with_version).unwrap();
+        sync_jsr_manifest_version(&manifest_path, &version).unwrap();
+        assert_eq!(
+            read_to_string(&manifest_path).unwrap(),
+            with_version.replace("1.2.3", "1.2.4")
+        );
+
+        let without_version = "{\n  \"tasks\": {}\n}\n";
+        write(&manifest_path, without_version).unwrap();
+        sync_jsr_manifest_version(&manifest_path, &version).unwrap();
+        assert_eq!(read_to_string(&manifest_path).unwrap(), without_version);
+
+        remove_file(manifest_path).unwrap();
+    }
+
+    const VERSION_RB: &str = "# frozen_string_literal: true
+
+module Example
+  VERSION = \"1.2.3\"
+end
+";
+
+    #[test]
+    fn test_version_rb() {
+        assert_eq!(version_rb_get_version(VERSION_RB).as_deref(), Some("1.2.3"));
+        assert_eq!(version_rb_get_version("module Example\nend\n"), None);
+
+        let version = Version::parse("1.3.0").unwrap();
+        let updated = version_rb_set_version(VERSION_RB, &version).unwrap();
+        assert_eq!(updated, VERSION_RB.replace("1.2.3", "1.3.0"));
+
+        // Pre-releases use the RubyGems form in the file, and the semver form otherwise.
+        let current = Version::parse(&version_rb_get_version(VERSION_RB).unwrap()).unwrap();
+        let dev = bump_version(
+            current,
+            &VersionBumpMagnitude::Dev(VersionBumpDevArgs {
+                bump_component: None,
+            }),
+        );
+        let updated = version_rb_set_version(VERSION_RB, &dev).unwrap();
+        assert!(updated.contains("VERSION = \"1.2.4.dev\""));
+        assert_eq!(
+            version_rb_get_version(&updated).as_deref(),
+            Some("1.2.4-dev")
+        );
+        let updated =
+            version_rb_set_version(VERSION_RB, &Version::parse("1.2.4-dev.4").unwrap()).unwrap();
+        assert!(updated.contains("VERSION = \"1.2.4.dev.4\""));
+        assert_eq!(
+            version_rb_get_version(&updated).as_deref(),
+            Some("1.2.4-dev.4")
+        );
+        assert_eq!(
+            version_rb_get_version("VERSION = '1.2.4.4'\n").as_deref(),
+            Some("1.2.4.4")
+        );
+    }
+
+    #[test]
+    fn test_gemspec_version_file_candidates() {
+        assert_eq!(
+            gemspec_version_file_candidates(
+                "require_relative \"lib/example/version\"\n\nGem::Specification.new do |spec|\nend\n"
+            ),
+            vec![PathBuf::from("lib/example/version.rb")]
+        );
+        assert_eq!(
+            gemspec_version_file_candidates(
+                "lib = File.expand_path(\"lib\", __dir__)\nrequire \"example/version\"\n"
+            ),
+            vec![PathBuf::from("lib/example/version.rb")]
+        );
+        assert!(
+            gemspec_version_file_candidates("Gem::Specification.new do |spec|\nend\n").is_empty()
+        );
+    }
 }
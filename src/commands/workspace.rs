@@ -1,8 +1,10 @@
-use std::process::exit;
-
 use clap::{Args, Subcommand, ValueEnum};
 
-use crate::common::{args::PathArgs, workspace::auto_detect_workspace_root};
+use crate::common::{
+    args::PathArgs,
+    error::{RepoError, RepoResult},
+    workspace::auto_detect_workspace_root,
+};
 
 #[derive(Args, Debug)]
 pub(crate) struct WorkspaceArgs {
@@ -37,10 +39,10 @@ enum RootFallback {
     ClosestDir,
 }
 
-pub(crate) fn workspace_command(workspace_args: WorkspaceArgs) {
+pub(crate) fn workspace_command(workspace_args: WorkspaceArgs) -> RepoResult<()> {
     match workspace_args.command {
         WorkspaceCommand::Root(workspace_root_args) => {
-            let path = &workspace_root_args.path_args.path();
+            let path = &workspace_root_args.path_args.path()?;
             let root_path = if let Some(path) = auto_detect_workspace_root(path) {
                 path
             } else {
@@ -52,17 +54,18 @@ pub(crate) fn workspace_command(workspace_args: WorkspaceArgs) {
                         } else if let Some(parent_path) = path.parent() {
                             parent_path.to_string_lossy().to_string()
                         } else {
-                            eprintln!("Could not get parent path");
-                            exit(1);
+                            return Err(RepoError::detection_failure("Could not get parent path"));
                         }
                     }
                     None => {
-                        eprintln!("No workspace found. Consider passing: `--fallback closest-dir`");
-                        exit(1)
+                        return Err(RepoError::detection_failure(
+                            "No workspace found. Consider passing: `--fallback closest-dir`",
+                        ));
                     }
                 }
             };
             print!("{}", root_path)
         }
     };
+    Ok(())
 }
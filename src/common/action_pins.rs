@@ -0,0 +1,368 @@
+use std::{
+    collections::HashMap,
+    fs::{read_dir, read_to_string, write},
+    path::PathBuf,
+};
+
+use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
+
+use crate::common::{
+    command::command_must_get_stdout,
+    error::{RepoError, RepoResult},
+};
+
+pub(crate) const DEFAULT_ACTIONS_REMOTE_BASE: &str = "https://github.com";
+const WORKFLOW_DIRS: [&str; 3] = [
+    "./.github/workflows",
+    "./.forgejo/workflows",
+    "./.gitea/workflows",
+];
+
+/// A `uses: owner/repo@ref # comment` line in a workflow.
+#[derive(Debug, PartialEq, Eq)]
+struct UsesLine<'a> {
+    /// Everything before the action (e.g. `      - uses: `).
+    prefix: &'a str,
+    action: &'a str,
+    reference: &'a str,
+    comment: Option<&'a str>,
+}
+
+/// Returns `None` for lines without `uses:` (and for local or Docker actions), and the `uses:` value if it can't be parsed.
+/// A trailing comment of any length is allowed, and its first word is used as the version comment.
+fn parse_uses_line(line: &str) -> Option<Result<UsesLine<'_>, &str>> {
+    let uses = static_regex!(r"^(\s*(?:-\s*)?uses:\s*)([^#]*?)\s*(?:#\s*(.*?))?\s*$");
+    let captures = uses.captures(line)?;
+    let value = captures.get(2)?.as_str();
+    let unquoted = ["\"", "'"]
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value);
+    // Local actions and Docker images are not pinned using tags.
+    if unquoted.starts_with("./") || unquoted.starts_with("docker://") {
+        return None;
+    }
+    let action_and_reference = static_regex!(r"^([^\s@\x22']+)@([^\s\x22']+)$");
+    let Some(action_captures) = action_and_reference.captures(unquoted) else {
+        return Some(Err(value));
+    };
+    Some(Ok(UsesLine {
+        prefix: captures.get(1)?.as_str(),
+        action: action_captures.get(1)?.as_str(),
+        reference: action_captures.get(2)?.as_str(),
+        comment: captures
+            .get(3)
+            .and_then(|comment| comment.as_str().split_whitespace().next()),
+    }))
+}
+
+fn is_commit_sha(reference: &str) -> bool {
+    static_regex!(r"^[0-9a-f]{40}$").is_match(reference)
+}
+
+/// The repository for an action, e.g. `https://github.com/actions/checkout` for `actions/checkout` or `github/codeql-action/init`.
+/// Actions that are already URLs (as used by Forgejo) are fetched from that URL instead of `remote_base`.
+fn action_repository_url(action: &str, remote_base: &str) -> Option<String> {
+    if let Some((scheme, rest)) = action.split_once("://") {
+        let mut segments = rest.split('/');
+        let (host, owner, repo) = (segments.next()?, segments.next()?, segments.next()?);
+        return Some(format!("{}://{}/{}/{}", scheme, host, owner, repo));
+    }
+    let mut segments = action.split('/');
+    let (owner, repo) = (segments.next()?, segments.next()?);
+    Some(format!(
+        "{}/{}/{}",
+        remote_base.trim_end_matches('/'),
+        owner,
+        repo
+    ))
+}
+
+fn semver_key(tag: &str) -> Option<(u64, u64, u64)> {
+    let semver = static_regex!(r"^v?(\d+)\.(\d+)\.(\d+)$");
+    let captures = semver.captures(tag)?;
+    Some((
+        captures[1].parse().ok()?,
+        captures[2].parse().ok()?,
+        captures[3].parse().ok()?,
+    ))
+}
+
+/// The refs of remote repositories, fetched once per repository using `git ls-remote`.
+struct RemoteRefs {
+    remote_base: String,
+    refs_by_url: HashMap<String, Vec<(String, String)>>,
+}
+
+impl RemoteRefs {
+    fn new(remote_base: &str) -> Self {
+        Self {
+            remote_base: remote_base.to_owned(),
+            refs_by_url: HashMap::default(),
+        }
+    }
+
+    /// Returns `(ref name, commit SHA)` pairs.
+    fn refs(&mut self, action: &str) -> RepoResult<&Vec<(String, String)>> {
+        let Some(url) = action_repository_url(action, &self.remote_base) else {
+            return Err(RepoError::user_error(format!(
+                "Could not determine the repository for action: {}",
+                action
+            )));
+        };
+        if !self.refs_by_url.contains_key(&url) {
+            let mut command = PrintableShellCommand::new("git");
+            command.args(["ls-remote", "--tags", "--heads", &url]);
+            let refs = command_must_get_stdout(command)?
+                .lines()
+                .filter_map(|line| {
+                    let (sha, ref_name) = line.split_once('\t')?;
+                    Some((ref_name.to_owned(), sha.to_owned()))
+                })
+                .collect();
+            self.refs_by_url.insert(url.clone(), refs);
+        }
+        Ok(&self.refs_by_url[&url])
+    }
+
+    /// Resolves a tag (preferring the commit of an annotated tag) or branch to a commit SHA.
+    fn resolve(&mut self, action: &str, reference: &str) -> RepoResult<Option<String>> {
+        let refs = self.refs(action)?;
+        for ref_name in [
+            format!("refs/tags/{}^{{}}", reference),
+            format!("refs/tags/{}", reference),
+            format!("refs/heads/{}", reference),
+        ] {
+            if let Some((_, sha)) = refs.iter().find(|(name, _)| name == &ref_name) {
+                return Ok(Some(sha.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    fn latest_semver_tag(&mut self, action: &str) -> RepoResult<Option<String>> {
+        Ok(self
+            .refs(action)?
+            .iter()
+            .filter_map(|(ref_name, _)| ref_name.strip_prefix("refs/tags/"))
+            .filter(|tag| !tag.ends_with("^{}"))
+            .filter_map(|tag| semver_key(tag).map(|key| (key, tag)))
+            .max()
+            .map(|(_, tag)| tag.to_owned()))
+    }
+}
+
+/// Workflow files in `.github/workflows/`, `.forgejo/workflows/`, and `.gitea/workflows/`.
+pub(crate) fn discover_workflow_files() -> RepoResult<Vec<PathBuf>> {
+    let mut paths = vec![];
+    for dir in WORKFLOW_DIRS {
+        let Ok(entries) = read_dir(dir) else {
+            continue;
+        };
+        for entry in entries {
+            let path = entry?.path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "yaml" || extension == "yml")
+            {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn workflow_files_or_discover(paths: Vec<PathBuf>) -> RepoResult<Vec<PathBuf>> {
+    let paths = if paths.is_empty() {
+        discover_workflow_files()?
+    } else {
+        paths
+    };
+    if paths.is_empty() {
+        return Err(RepoError::detection_failure("No workflow files found."));
+    }
+    Ok(paths)
+}
+
+/// Rewrites `uses:` lines to `owner/repo@<sha> # <tag>`.
+/// The tag is taken from the version comment of lines that are already pinned (or the latest semver tag, if `latest` is set).
+pub(crate) fn pin_workflow_actions(
+    paths: Vec<PathBuf>,
+    remote_base: &str,
+    latest: bool,
+) -> RepoResult<()> {
+    let mut remote_refs = RemoteRefs::new(remote_base);
+    for path in workflow_files_or_discover(paths)? {
+        let source = read_to_string(&path)?;
+        let mut lines = vec![];
+        let mut num_changed = 0;
+        for line in source.lines() {
+            let Some(uses_line) = parse_uses_line(line) else {
+                lines.push(line.to_owned());
+                continue;
+            };
+            let Ok(uses_line) = uses_line else {
+                eprintln!("Skipping (could not parse): {}", line.trim());
+                lines.push(line.to_owned());
+                continue;
+            };
+            let tag = if latest {
+                remote_refs.latest_semver_tag(uses_line.action)?
+            } else if is_commit_sha(uses_line.reference) {
+                uses_line.comment.map(str::to_owned)
+            } else {
+                Some(uses_line.reference.to_owned())
+            };
+            let Some(tag) = tag else {
+                eprintln!(
+                    "Skipping (no version to pin to): {}@{}",
+                    uses_line.action, uses_line.reference
+                );
+                lines.push(line.to_owned());
+                continue;
+            };
+            let Some(sha) = remote_refs.resolve(uses_line.action, &tag)? else {
+                return Err(RepoError::user_error(format!(
+                    "Could not find `{}` for action: {}",
+                    tag, uses_line.action
+                )));
+            };
+            let pinned_line = format!("{}{}@{} # {}", uses_line.prefix, uses_line.action, sha, tag);
+            if pinned_line != line {
+                eprintln!("Pinning: {}@{} # {}", uses_line.action, sha, tag);
+                num_changed += 1;
+            }
+            lines.push(pinned_line);
+        }
+        if num_changed > 0 {
+            let mut contents = lines.join("\n");
+            if source.ends_with('\n') {
+                contents.push('\n');
+            }
+            write(&path, contents)?;
+        }
+        println!(
+            "{}: {} action(s) pinned",
+            path.to_string_lossy(),
+            num_changed
+        );
+    }
+    Ok(())
+}
+
+/// Fails if any `uses:` line is not pinned to a commit SHA, or has a version comment that resolves to a different commit.
+pub(crate) fn lint_workflow_actions(paths: Vec<PathBuf>, remote_base: &str) -> RepoResult<()> {
+    let mut remote_refs = RemoteRefs::new(remote_base);
+    let mut num_problems = 0;
+    for path in workflow_files_or_discover(paths)? {
+        let source = read_to_string(&path)?;
+        for (i, line) in source.lines().enumerate() {
+            let Some(uses_line) = parse_uses_line(line) else {
+                continue;
+            };
+            let location = format!("{}:{}", path.to_string_lossy(), i + 1);
+            let uses_line = match uses_line {
+                Ok(uses_line) => uses_line,
+                Err(value) => {
+                    println!(
+                        "❌ {} Could not parse the `uses:` value `{}`.",
+                        location, value
+                    );
+                    num_problems += 1;
+                    continue;
+                }
+            };
+            if !is_commit_sha(uses_line.reference) {
+                println!(
+                    "❌ {} `{}@{}` is not pinned to a commit SHA.",
+                    location, uses_line.action, uses_line.reference
+                );
+                num_problems += 1;
+                continue;
+            }
+            let Some(comment) = uses_line.comment else {
+                continue;
+            };
+            let sha = remote_refs.resolve(uses_line.action, comment)?;
+            if sha.as_deref() != Some(uses_line.reference) {
+                println!(
+                    "❌ {} `{}` is pinned to `{}`, but `{}` is {}.",
+                    location,
+                    uses_line.action,
+                    uses_line.reference,
+                    comment,
+                    sha.map_or_else(
+                        || "not a tag or branch".to_owned(),
+                        |sha| format!("`{}`", sha)
+                    )
+                );
+                num_problems += 1;
+            }
+        }
+    }
+    if num_problems > 0 {
+        return Err(RepoError::check_failed(format!(
+            "Found {} problem(s) with pinned actions.",
+            num_problems
+        )));
+    }
+    println!("✅ All actions are pinned.");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::action_pins::{action_repository_url, parse_uses_line, UsesLine};
+
+    #[test]
+    fn test_parse_uses_line() {
+        assert_eq!(
+            parse_uses_line(
+                "      - uses: actions/checkout@de0fac2e4500dabe0009e67214ff5f5447ce83dd #v6.0.2"
+            ),
+            Some(Ok(UsesLine {
+                prefix: "      - uses: ",
+                action: "actions/checkout",
+                reference: "de0fac2e4500dabe0009e67214ff5f5447ce83dd",
+                comment: Some("v6.0.2"),
+            }))
+        );
+        assert_eq!(
+            parse_uses_line("      - uses: actions/checkout@v4 # TODO pin later"),
+            Some(Ok(UsesLine {
+                prefix: "      - uses: ",
+                action: "actions/checkout",
+                reference: "v4",
+                comment: Some("TODO"),
+            }))
+        );
+        assert_eq!(
+            parse_uses_line("        uses: \"actions/setup-node@v4\""),
+            Some(Ok(UsesLine {
+                prefix: "        uses: ",
+                action: "actions/setup-node",
+                reference: "v4",
+                comment: None,
+            }))
+        );
+        assert_eq!(
+            parse_uses_line("      - uses: actions/checkout"),
+            Some(Err("actions/checkout"))
+        );
+        assert_eq!(parse_uses_line("      - run: make test # uses: x@y"), None);
+        assert_eq!(
+            parse_uses_line("      - uses: ./.github/actions/local@v1"),
+            None
+        );
+        assert_eq!(
+            action_repository_url("github/codeql-action/init", "https://github.com/"),
+            Some("https://github.com/github/codeql-action".to_owned())
+        );
+        assert_eq!(
+            action_repository_url("https://github.com/actions/checkout", "/tmp/remotes"),
+            Some("https://github.com/actions/checkout".to_owned())
+        );
+    }
+}
@@ -2,6 +2,8 @@ use std::{env::current_dir, path::PathBuf};
 
 use clap::Args;
 
+use crate::common::error::RepoResult;
+
 #[derive(Args, Debug)]
 pub(crate) struct PathArgs {
     /// Path to an existing file or folder to use. Defaults to the current working directory.
@@ -11,10 +13,10 @@ pub(crate) struct PathArgs {
 }
 
 impl PathArgs {
-    pub(crate) fn path(&self) -> PathBuf {
-        match &self.maybe_path {
+    pub(crate) fn path(&self) -> RepoResult<PathBuf> {
+        Ok(match &self.maybe_path {
             Some(path) => path.clone(),
-            None => current_dir().expect("Could not access the current working directory."),
-        }
+            None => current_dir()?,
+        })
     }
 }
@@ -0,0 +1,95 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, exists, read_to_string, write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::common::{
+    error::{RepoError, RepoResult},
+    template::TemplateVariableArgs,
+};
+
+const BOILERPLATE_LOCKFILE_PATH: &str = "./.config/repo-boilerplate.json";
+
+/// Records the template contents that each boilerplate file was generated from, so that `repo boilerplate upgrade` can three-way merge newer templates into customized files.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub(crate) struct BoilerplateLockfile {
+    #[serde(default)]
+    pub(crate) files: BTreeMap<String, BoilerplateLockfileEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct BoilerplateLockfileEntry {
+    /// `sha256-` followed by the hex digest of `base`.
+    pub(crate) hash: String,
+    /// The rendered template, used as the merge base.
+    pub(crate) base: String,
+    /// Variable overrides that were passed when the file was generated.
+    #[serde(default, skip_serializing_if = "TemplateVariableArgs::is_empty")]
+    pub(crate) variables: TemplateVariableArgs,
+}
+
+pub(crate) fn content_hash(contents: &[u8]) -> String {
+    format!("sha256-{:x}", Sha256::digest(contents))
+}
+
+/// The key used in the lockfile, e.g. `.github/workflows/CI.yaml` for `./.github/workflows/CI.yaml`.
+pub(crate) fn lockfile_key(relative_path: &Path) -> String {
+    relative_path
+        .strip_prefix(".")
+        .unwrap_or(relative_path)
+        .to_string_lossy()
+        .to_string()
+}
+
+impl BoilerplateLockfile {
+    pub(crate) fn read() -> RepoResult<Self> {
+        if !exists(BOILERPLATE_LOCKFILE_PATH)? {
+            return Ok(Self::default());
+        }
+        serde_json::from_str(&read_to_string(BOILERPLATE_LOCKFILE_PATH)?).map_err(|e| {
+            RepoError::config(format!(
+                "Could not parse boilerplate lockfile ({}): {}",
+                BOILERPLATE_LOCKFILE_PATH, e
+            ))
+        })
+    }
+
+    pub(crate) fn write(&self) -> RepoResult<()> {
+        if let Some(parent) = Path::new(BOILERPLATE_LOCKFILE_PATH).parent() {
+            create_dir_all(parent)?;
+        }
+        let Ok(json) = serde_json::to_string_pretty(self) else {
+            return Err(RepoError::internal(
+                "Could not serialize boilerplate lockfile.",
+            ));
+        };
+        write(BOILERPLATE_LOCKFILE_PATH, format!("{}\n", json))?;
+        Ok(())
+    }
+
+    /// Records (and writes) the rendered template for the given file.
+    /// Binary templates are not recorded, since they cannot be merged.
+    pub(crate) fn record(
+        relative_path: &Path,
+        rendered_bytes: &[u8],
+        variables: &TemplateVariableArgs,
+    ) -> RepoResult<()> {
+        let Ok(base) = String::from_utf8(rendered_bytes.to_vec()) else {
+            return Ok(());
+        };
+        let mut lockfile = Self::read()?;
+        lockfile.files.insert(
+            lockfile_key(relative_path),
+            BoilerplateLockfileEntry {
+                hash: content_hash(rendered_bytes),
+                base,
+                variables: variables.clone(),
+            },
+        );
+        lockfile.write()
+    }
+}
@@ -0,0 +1,192 @@
+use std::fs::{read_to_string, write};
+
+use regex_static::static_regex;
+use toml_edit::{value, DocumentMut};
+
+use crate::common::{
+    error::{RepoError, RepoResult},
+    package_manager::PACKAGE_JSON_PATH,
+    structured_merge::{merge_toml, MergePolicies},
+    template::CARGO_TOML_PATH,
+    vcs::auto_detect_remote_url,
+};
+
+// Matches the artifact names of `repo boilerplate release-binaries`.
+// The leading newlines separate the tables from the preceding ones.
+const BINSTALL_METADATA: &str = r#"
+[package.metadata.binstall]
+pkg-url = "{ repo }/releases/download/v{ version }/{ name }.v{ version }.{ target }"
+pkg-fmt = "bin"
+"#;
+
+const SIZE_OPTIMIZED_RELEASE_PROFILE: &str = r#"
+# Optimize for release binary size.
+[profile.release]
+strip = true
+opt-level = "z"
+lto = true
+codegen-units = 1
+panic = "abort"
+"#;
+
+fn read_cargo_toml() -> RepoResult<String> {
+    read_to_string(CARGO_TOML_PATH)
+        .map_err(|_| RepoError::detection_failure("Could not read `Cargo.toml`."))
+}
+
+pub(crate) fn add_binstall_metadata() -> RepoResult<()> {
+    let source = read_cargo_toml()?;
+    let contents = merge_toml(&source, BINSTALL_METADATA, MergePolicies::default())?;
+    if contents == source {
+        eprintln!("`Cargo.toml` already has `cargo binstall` metadata.");
+        return Ok(());
+    }
+    write(CARGO_TOML_PATH, contents)?;
+    eprintln!("Added `cargo binstall` metadata to `Cargo.toml`.");
+    Ok(())
+}
+
+/// The web URL for a remote, e.g. `https://github.com/owner/repo` for `git@github.com:owner/repo.git`.
+/// Returns `None` for local remotes.
+pub(crate) fn repository_url_from_remote(remote_url: &str) -> Option<String> {
+    let remote_url = remote_url.trim_end_matches('/');
+    let remote_url = remote_url.strip_suffix(".git").unwrap_or(remote_url);
+    let captures = static_regex!(
+        r"^(?:(?:https?|ssh|git)://)?(?:[^@/]+@)?([^:/]+)(?::\d+)?[:/]([^:]+/[^:]+)$"
+    )
+    .captures(remote_url)?;
+    // Paths like `../repo` or `/srv/repo` are not hosted anywhere.
+    if captures[1].starts_with('.')
+        || remote_url.starts_with('/')
+        || remote_url.starts_with("file:")
+    {
+        return None;
+    }
+    Some(format!("https://{}/{}", &captures[1], &captures[2]))
+}
+
+fn package_json_string_field(key: &str) -> Option<String> {
+    let package_json = read_to_string(PACKAGE_JSON_PATH).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&package_json).ok()?;
+    value[key].as_str().map(str::to_owned)
+}
+
+pub(crate) struct PackageFields {
+    pub(crate) repository: Option<String>,
+    pub(crate) license: Option<String>,
+    pub(crate) description: Option<String>,
+}
+
+impl PackageFields {
+    /// Falls back to the `origin` remote (for `repository`) and `package.json` for any value that is not given.
+    pub(crate) fn or_detect(self) -> Self {
+        Self {
+            repository: self.repository.or_else(|| {
+                auto_detect_remote_url().and_then(|url| repository_url_from_remote(&url))
+            }),
+            license: self
+                .license
+                .or_else(|| package_json_string_field("license")),
+            description: self
+                .description
+                .or_else(|| package_json_string_field("description")),
+        }
+    }
+}
+
+/// Edits `Cargo.toml` in place (preserving its formatting), only adding what is missing.
+pub(crate) fn update_cargo_toml_package(
+    fields: PackageFields,
+    binstall_metadata: bool,
+    size_optimized_release_profile: bool,
+) -> RepoResult<()> {
+    let source = read_cargo_toml()?;
+    let mut document: DocumentMut = source
+        .parse()
+        .map_err(|e| RepoError::user_error(format!("`Cargo.toml` is not valid TOML: {}", e)))?;
+    // Workspace roots without a `[package]` don't have packaging fields.
+    let Some(package) = document
+        .get_mut("package")
+        .and_then(|package| package.as_table_like_mut())
+    else {
+        return Err(RepoError::user_error(
+            "`Cargo.toml` has no `[package]` table.",
+        ));
+    };
+    for (key, field_value) in [
+        ("description", fields.description),
+        ("license", fields.license),
+        ("repository", fields.repository),
+    ] {
+        // Also covers inherited values like `license.workspace = true`.
+        if package.contains_key(key) {
+            continue;
+        }
+        match field_value {
+            Some(field_value) => {
+                eprintln!("Setting `package.{}`: {}", key, field_value);
+                package.insert(key, value(field_value));
+            }
+            None => eprintln!(
+                "⚠️ Could not determine `package.{}` (pass `--{}`).",
+                key, key
+            ),
+        }
+    }
+    let mut contents = document.to_string();
+    if binstall_metadata {
+        contents = merge_toml(&contents, BINSTALL_METADATA, MergePolicies::default())?;
+    }
+    if size_optimized_release_profile {
+        let has_release_profile = document
+            .get("profile")
+            .and_then(|profile| profile.get("release"))
+            .is_some();
+        if has_release_profile {
+            eprintln!("Keeping the existing `[profile.release]`.");
+        } else {
+            // Appended as text so that the explanatory comment is kept.
+            contents = format!(
+                "{}\n{}",
+                contents.trim_end_matches('\n'),
+                SIZE_OPTIMIZED_RELEASE_PROFILE
+            );
+        }
+    }
+    if contents == source {
+        println!("Already up to date: {}", CARGO_TOML_PATH);
+        return Ok(());
+    }
+    write(CARGO_TOML_PATH, contents)?;
+    println!("Updated: {}", CARGO_TOML_PATH);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::cargo_toml::repository_url_from_remote;
+
+    #[test]
+    fn test_repository_url_from_remote() {
+        for remote_url in [
+            "git@github.com:owner/repo.git",
+            "https://github.com/owner/repo.git",
+            "https://user@github.com/owner/repo",
+            "ssh://git@github.com:22/owner/repo.git",
+        ] {
+            assert_eq!(
+                repository_url_from_remote(remote_url),
+                Some("https://github.com/owner/repo".to_owned()),
+                "{}",
+                remote_url
+            );
+        }
+        assert_eq!(
+            repository_url_from_remote("https://gitlab.com/group/subgroup/repo.git"),
+            Some("https://gitlab.com/group/subgroup/repo".to_owned())
+        );
+        assert_eq!(repository_url_from_remote("../repo"), None);
+        assert_eq!(repository_url_from_remote("/srv/git/repo.git"), None);
+        assert_eq!(repository_url_from_remote("file:///srv/git/repo"), None);
+    }
+}
@@ -0,0 +1,474 @@
+use std::fs::{exists, read_to_string};
+
+use regex_static::static_regex;
+
+use crate::common::{
+    ecosystem::Ecosystem,
+    forge::VCSForge,
+    makefile::{has_makefile_target, MAKEFILE_PATH},
+    package_manager::PackageManager,
+    template::{TemplateVariables, RUST_TOOLCHAIN_TOML_PATH},
+};
+
+const CHECKOUT_ACTION: &str = "actions/checkout@de0fac2e4500dabe0009e67214ff5f5447ce83dd # v6.0.2";
+const SETUP_NODE_ACTION: &str =
+    "actions/setup-node@48b55a011bda9f5d6aeb4c2d9c7362e8dae4041e # v6.4.0";
+const SETUP_BUN_ACTION: &str =
+    "oven-sh/setup-bun@0c5077e51419868618aeaa5fe8019c62421857d6 # v2.2.0";
+const RUST_CACHE_ACTION: &str =
+    "Swatinem/rust-cache@c19371144df3bb44fab255c43d04cbc2ab54d1c4 # v2.9.1";
+const GH_RELEASE_ACTION: &str =
+    "softprops/action-gh-release@b4309332981a82ec1c5618f44dd2e27cc8bfbfda # v3.0.0";
+// Pinned so that container-based runs (Woodpecker, GitLab) are reproducible.
+const CONTAINER_BUN_VERSION: &str = "1.3.2";
+
+// Used when there is no `Makefile` yet.
+const DEFAULT_MAKE_TARGETS: [&str; 2] = ["lint", "test"];
+const CANDIDATE_MAKE_TARGETS: [&str; 4] = ["setup", "lint", "test", "build"];
+
+/// Setup steps for a CI job, in order. Forges that don't support a step (e.g. caching) skip it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum CISetupStep {
+    /// Node, with the dependency cache for the given package manager (if supported by `setup-node`).
+    Node {
+        cache: Option<&'static str>,
+    },
+    Bun,
+    /// Provides `pnpm` and `yarn`, using the version in `package.json` (if any).
+    Corepack,
+    /// Installs the toolchain from `rust-toolchain.toml`.
+    RustToolchain,
+    RustCache,
+}
+
+/// A single CI job that sets up the project and runs `make` targets.
+/// Node versions are emitted as `{{node_version}}` so that the result can be rendered like any other template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CIPipeline {
+    pub(crate) setup: Vec<CISetupStep>,
+    pub(crate) make_targets: Vec<String>,
+}
+
+impl CIPipeline {
+    /// The steps needed for the given ecosystems (which may be overridden, e.g. using `--ecosystem`) and the detected package managers.
+    pub(crate) fn from_template_variables(template_variables: &TemplateVariables) -> Self {
+        let mut setup = vec![];
+        if template_variables
+            .ecosystems
+            .contains(&Ecosystem::JavaScript)
+        {
+            match PackageManager::auto_detect_preferred_package_manager_for_ecosystem(
+                Ecosystem::JavaScript,
+            ) {
+                Some(PackageManager::Bun) => {
+                    setup.push(CISetupStep::Node { cache: None });
+                    setup.push(CISetupStep::Bun);
+                }
+                Some(PackageManager::Pnpm | PackageManager::Yarn) => {
+                    setup.push(CISetupStep::Node { cache: None });
+                    setup.push(CISetupStep::Corepack);
+                }
+                _ => setup.push(CISetupStep::Node { cache: Some("npm") }),
+            }
+        }
+        if template_variables.ecosystems.contains(&Ecosystem::Rust) {
+            if exists(RUST_TOOLCHAIN_TOML_PATH).unwrap_or(false) {
+                setup.push(CISetupStep::RustToolchain);
+            }
+            setup.push(CISetupStep::RustCache);
+        }
+
+        let make_targets = match read_to_string(MAKEFILE_PATH) {
+            Ok(makefile) => CANDIDATE_MAKE_TARGETS
+                .into_iter()
+                .filter(|target| has_makefile_target(&makefile, target))
+                .map(str::to_owned)
+                .collect(),
+            Err(_) => DEFAULT_MAKE_TARGETS.map(str::to_owned).to_vec(),
+        };
+
+        Self {
+            setup,
+            make_targets,
+        }
+    }
+
+    fn has_step(&self, step: &CISetupStep) -> bool {
+        self.setup.contains(step)
+    }
+
+    fn uses_node(&self) -> bool {
+        self.setup
+            .iter()
+            .any(|step| matches!(step, CISetupStep::Node { .. }))
+    }
+
+    fn uses_rust(&self) -> bool {
+        self.has_step(&CISetupStep::RustCache)
+    }
+
+    pub(crate) fn render(&self, forge: VCSForge) -> String {
+        match forge {
+            VCSForge::GitHub => self.render_github_actions("", "ubuntu-latest"),
+            VCSForge::Codeberg => self.render_woodpecker(),
+            VCSForge::GitLab => self.render_gitlab_ci(),
+            // Forgejo runners resolve bare action names against their own mirror, so we reference the pinned actions on GitHub directly.
+            VCSForge::Forgejo => self.render_github_actions("https://github.com/", "docker"),
+        }
+    }
+
+    fn render_github_actions(&self, action_prefix: &str, runs_on: &str) -> String {
+        let uses = |action: &str| format!("- uses: {}{}", action_prefix, action);
+        let mut steps = vec![uses(CHECKOUT_ACTION)];
+        for step in &self.setup {
+            steps.push(match step {
+                CISetupStep::Node { cache } => {
+                    let mut step = format!(
+                        "{}\n  with:\n    node-version: {{{{node_version}}}}",
+                        uses(SETUP_NODE_ACTION)
+                    );
+                    if let Some(cache) = cache {
+                        step.push_str(&format!("\n    cache: {}", cache));
+                    }
+                    step
+                }
+                CISetupStep::Bun => uses(SETUP_BUN_ACTION),
+                CISetupStep::Corepack => "- run: corepack enable".to_owned(),
+                CISetupStep::RustToolchain => "- run: rustup toolchain install".to_owned(),
+                CISetupStep::RustCache => uses(RUST_CACHE_ACTION),
+            });
+        }
+        for make_target in &self.make_targets {
+            steps.push(format!("- run: make {}", make_target));
+        }
+        format!(
+            "name: CI
+
+on: [ push, pull_request ]
+
+jobs:
+  test:
+    runs-on: {}
+
+    steps:
+{}",
+            runs_on,
+            indent(&steps, "      ")
+        )
+    }
+
+    /// For forges that run a list of shell commands in a single container.
+    /// We pick an image for the primary ecosystem and install the rest.
+    /// `cargo_home` is where `rustup` installs (if it is installed by the commands).
+    fn container_image_and_commands(&self, cargo_home: &str) -> (&'static str, Vec<String>) {
+        let image = if self.uses_node() {
+            "node:{{node_version}}"
+        } else if self.uses_rust() {
+            "rust"
+        } else {
+            "debian"
+        };
+        let mut commands = vec![];
+        if self.uses_node() && self.uses_rust() {
+            commands.push(
+                "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y"
+                    .to_owned(),
+            );
+            commands.push(format!(". \"{}/env\"", cargo_home));
+        }
+        for step in &self.setup {
+            match step {
+                CISetupStep::Bun => commands.push(format!(
+                    "npm install --global bun@{}",
+                    CONTAINER_BUN_VERSION
+                )),
+                CISetupStep::Corepack => commands.push("corepack enable".to_owned()),
+                CISetupStep::RustToolchain => commands.push("rustup toolchain install".to_owned()),
+                CISetupStep::Node { .. } | CISetupStep::RustCache => {}
+            }
+        }
+        for make_target in &self.make_targets {
+            commands.push(format!("make {}", make_target));
+        }
+        let commands = commands
+            .into_iter()
+            .map(|command| format!("- {}", command))
+            .collect();
+        (image, commands)
+    }
+
+    fn render_woodpecker(&self) -> String {
+        let (image, commands) = self.container_image_and_commands("$HOME/.cargo");
+        format!(
+            "when:
+  - event: push
+    branch: main
+
+steps:
+  - name: check
+    image: {}
+    commands:
+{}",
+            image,
+            indent(&commands, "      ")
+        )
+    }
+
+    fn render_gitlab_ci(&self) -> String {
+        // `CARGO_HOME` is set (below) so that it can be cached.
+        let (image, commands) = self.container_image_and_commands("$CARGO_HOME");
+        let mut variables = vec![];
+        let mut cache_paths = vec![];
+        for step in &self.setup {
+            match step {
+                CISetupStep::Node { cache: Some("npm") } => {
+                    variables.push("npm_config_cache: $CI_PROJECT_DIR/.npm".to_owned());
+                    cache_paths.push("- .npm/".to_owned());
+                }
+                CISetupStep::RustCache => {
+                    variables.push("CARGO_HOME: $CI_PROJECT_DIR/.cargo".to_owned());
+                    cache_paths.push("- .cargo/".to_owned());
+                    cache_paths.push("- target/".to_owned());
+                }
+                _ => {}
+            }
+        }
+        let mut job = format!("check:\n  image: {}\n", image);
+        if !variables.is_empty() {
+            job.push_str(&format!("  variables:\n{}", indent(&variables, "    ")));
+            job.push_str(&format!(
+                "  cache:\n    paths:\n{}",
+                indent(&cache_paths, "      ")
+            ));
+        }
+        job.push_str(&format!("  script:\n{}", indent(&commands, "    ")));
+        job
+    }
+}
+
+pub(crate) const RELEASE_BINARIES_WORKFLOW_PATH: &str =
+    "./.github/workflows/build-release-binaries.yaml";
+pub(crate) const DEFAULT_RELEASE_TARGETS: [&str; 6] = [
+    "x86_64-apple-darwin",
+    "aarch64-apple-darwin",
+    "x86_64-pc-windows-msvc",
+    "aarch64-pc-windows-msvc",
+    "x86_64-unknown-linux-gnu",
+    "aarch64-unknown-linux-gnu",
+];
+
+/// A GitHub-hosted runner that can build the given target triple natively (or, for macOS, using the bundled SDK).
+fn release_target_runner(target: &str) -> &'static str {
+    if target.contains("-apple-") {
+        "macos-latest"
+    } else if target.contains("-windows") {
+        if target.starts_with("aarch64-") {
+            "windows-11-arm"
+        } else {
+            "windows-latest"
+        }
+    } else if target.starts_with("aarch64-") {
+        "ubuntu-24.04-arm"
+    } else {
+        "ubuntu-latest"
+    }
+}
+
+/// Reads the target triples from the matrix of an existing release binaries workflow.
+pub(crate) fn release_targets_from_workflow(source: &str) -> Vec<String> {
+    let matrix_target = static_regex!(r"(?m)^\s+- target: (\S+)$");
+    matrix_target
+        .captures_iter(source)
+        .map(|captures| captures[1].to_owned())
+        .collect()
+}
+
+/// A workflow that builds a binary for each target when a version tag is pushed, and uploads them to the GitHub release.
+/// Binaries are named `{binary_name}.v{version}.{target}` to match the `pkg-url` pattern from `cargo binstall` metadata (where `{ name }` is the crate name).
+pub(crate) fn release_binaries_workflow(binary_name: &str, targets: &[String]) -> String {
+    let matrix: Vec<String> = targets
+        .iter()
+        .map(|target| {
+            let mut entry = format!(
+                "- target: {}\n  runs-on: {}",
+                target,
+                release_target_runner(target)
+            );
+            if target.contains("-windows") {
+                entry.push_str("\n  exe-suffix: .exe");
+            }
+            entry
+        })
+        .collect();
+    let binary_path = format!(
+        "./target/${{{{ matrix.target }}}}/release/{}${{{{ matrix.exe-suffix }}}}",
+        binary_name
+    );
+    let artifact_name = format!(
+        "{}.${{{{ github.ref_name }}}}.${{{{ matrix.target }}}}",
+        binary_name
+    );
+    format!(
+        "name: Build release binaries
+
+on:
+  push:
+    tags:
+      - v*
+
+jobs:
+  build:
+    strategy:
+      matrix:
+        include:
+{}    runs-on: ${{{{ matrix.runs-on }}}}
+    permissions:
+      contents: write
+    steps:
+      - uses: {}
+      - uses: {}
+        with:
+          key: ${{{{ matrix.target }}}}
+      - run: rustup target add ${{{{ matrix.target }}}}
+      - run: cargo build --release --target ${{{{ matrix.target }}}}
+      - run: mv {} {}
+      - uses: {}
+        with:
+          files: {}
+",
+        indent(&matrix, "          "),
+        CHECKOUT_ACTION,
+        RUST_CACHE_ACTION,
+        binary_path,
+        artifact_name,
+        GH_RELEASE_ACTION,
+        artifact_name,
+    )
+}
+
+fn indent(blocks: &[String], prefix: &str) -> String {
+    blocks
+        .iter()
+        .flat_map(|block| block.lines())
+        .map(|line| format!("{}{}\n", prefix, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        ci::{release_binaries_workflow, release_targets_from_workflow, CIPipeline, CISetupStep},
+        ecosystem::Ecosystem,
+        forge::VCSForge,
+        template::TemplateVariables,
+    };
+
+    #[test]
+    fn test_ci_pipeline_uses_template_variable_ecosystems() {
+        let template_variables = |ecosystems| TemplateVariables {
+            project_name: "example".to_owned(),
+            ecosystems,
+            node_version: "24".to_owned(),
+            rust_toolchain_channel: "stable".to_owned(),
+            forge: VCSForge::GitHub,
+        };
+        let ci_pipeline =
+            CIPipeline::from_template_variables(&template_variables(vec![Ecosystem::JavaScript]));
+        assert!(ci_pipeline.uses_node());
+        assert!(!ci_pipeline.uses_rust());
+        let ci_pipeline =
+            CIPipeline::from_template_variables(&template_variables(vec![Ecosystem::Rust]));
+        assert!(!ci_pipeline.uses_node());
+        assert!(ci_pipeline.uses_rust());
+    }
+
+    #[test]
+    fn test_render_ci_pipeline() {
+        let ci_pipeline = CIPipeline {
+            setup: vec![
+                CISetupStep::Node { cache: Some("npm") },
+                CISetupStep::RustToolchain,
+                CISetupStep::RustCache,
+            ],
+            make_targets: vec!["setup".to_owned(), "test".to_owned()],
+        };
+        assert_eq!(
+            ci_pipeline.render(VCSForge::GitHub),
+            "name: CI
+
+on: [ push, pull_request ]
+
+jobs:
+  test:
+    runs-on: ubuntu-latest
+
+    steps:
+      - uses: actions/checkout@de0fac2e4500dabe0009e67214ff5f5447ce83dd # v6.0.2
+      - uses: actions/setup-node@48b55a011bda9f5d6aeb4c2d9c7362e8dae4041e # v6.4.0
+        with:
+          node-version: {{node_version}}
+          cache: npm
+      - run: rustup toolchain install
+      - uses: Swatinem/rust-cache@c19371144df3bb44fab255c43d04cbc2ab54d1c4 # v2.9.1
+      - run: make setup
+      - run: make test
+"
+        );
+        assert_eq!(
+            ci_pipeline.render(VCSForge::Codeberg),
+            "when:
+  - event: push
+    branch: main
+
+steps:
+  - name: check
+    image: node:{{node_version}}
+    commands:
+      - curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y
+      - . \"$HOME/.cargo/env\"
+      - rustup toolchain install
+      - make setup
+      - make test
+"
+        );
+        assert_eq!(
+            ci_pipeline.render(VCSForge::GitLab),
+            "check:
+  image: node:{{node_version}}
+  variables:
+    npm_config_cache: $CI_PROJECT_DIR/.npm
+    CARGO_HOME: $CI_PROJECT_DIR/.cargo
+  cache:
+    paths:
+      - .npm/
+      - .cargo/
+      - target/
+  script:
+    - curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh -s -- -y
+    - . \"$CARGO_HOME/env\"
+    - rustup toolchain install
+    - make setup
+    - make test
+"
+        );
+    }
+
+    #[test]
+    fn test_release_binaries_workflow_targets() {
+        let targets = vec![
+            "x86_64-unknown-linux-gnu".to_owned(),
+            "aarch64-pc-windows-msvc".to_owned(),
+        ];
+        let workflow = release_binaries_workflow("example", &targets);
+        assert!(workflow.contains(
+            "          - target: aarch64-pc-windows-msvc
+            runs-on: windows-11-arm
+            exe-suffix: .exe
+"
+        ));
+        assert!(workflow.contains(
+            "mv ./target/${{ matrix.target }}/release/example${{ matrix.exe-suffix }} example.${{ github.ref_name }}.${{ matrix.target }}"
+        ));
+        assert_eq!(release_targets_from_workflow(&workflow), targets);
+    }
+}
@@ -1,21 +1,71 @@
-use std::process::Stdio;
+use std::{io::ErrorKind, process::Stdio};
 
 use printable_shell_command::PrintableShellCommand;
 
-use crate::common::debug::DebugPrintable;
+use crate::common::{
+    debug::DebugPrintable,
+    error::{RepoError, RepoResult},
+};
 
-// Gracefully recovers from any error by returning `None`.
-// Currently trims the output.
-pub(crate) fn command_must_succeed(mut command: PrintableShellCommand) -> Result<(), String> {
+fn program_name(command: &PrintableShellCommand) -> String {
+    command.get_program().to_string_lossy().to_string()
+}
+
+pub(crate) fn spawn_error(command: &PrintableShellCommand, error: std::io::Error) -> RepoError {
+    let program = program_name(command);
+    if error.kind() == ErrorKind::NotFound {
+        RepoError::tool_missing(format!("Could not find `{}`. Is it installed?", program))
+    } else {
+        RepoError::tool_failed(format!("Could not run `{}`: {}", program, error))
+    }
+}
+
+/// Runs the command with captured output, and returns its `stdout` (trimmed).
+/// On failure, the error includes the command's `stderr`.
+pub(crate) fn command_must_get_stdout(mut command: PrintableShellCommand) -> RepoResult<String> {
     command.debug_print();
     command.stdout(Stdio::piped()).stderr(Stdio::piped());
-    let Ok(exit_status) = command.status() else {
-        return Ok(());
+    let output = command
+        .output()
+        .map_err(|error| spawn_error(&command, error))?;
+    if !output.status.success() {
+        return Err(RepoError::tool_failed(format!(
+            "`{}` failed ({}).",
+            program_name(&command),
+            output.status
+        ))
+        .with_stderr(String::from_utf8_lossy(&output.stderr)));
+    }
+    let Ok(stdout) = String::from_utf8(output.stdout) else {
+        return Err(RepoError::tool_failed(format!(
+            "`{}` produced output that is not valid UTF-8.",
+            program_name(&command)
+        )));
     };
+    Ok(stdout.trim().to_owned())
+}
 
-    if exit_status.code() == Some(0) {
-        return Ok(());
-    }
+/// Runs the command with captured output, discarding `stdout`.
+/// On failure, the error includes the command's `stderr`.
+pub(crate) fn command_must_succeed(command: PrintableShellCommand) -> RepoResult<()> {
+    command_must_get_stdout(command)?;
+    Ok(())
+}
 
-    Err(String::from_utf8(command.output().unwrap().stderr).unwrap())
+/// Runs the command with inherited `stdin`/`stdout`/`stderr`, so that the user can see its output and respond to prompts.
+pub(crate) fn command_must_succeed_interactively(
+    mut command: PrintableShellCommand,
+) -> RepoResult<()> {
+    command.debug_print();
+    let exit_status = command
+        .status()
+        .map_err(|error| spawn_error(&command, error))?;
+    if !exit_status.success() {
+        return Err(RepoError::tool_failed(format!(
+            "`{}` failed ({}).",
+            program_name(&command),
+            exit_status
+        )));
+    }
+    Ok(())
 }
@@ -3,8 +3,8 @@ use printable_shell_command::PrintableShellCommand;
 use crate::{
     commands::version::CommitOperationArgs,
     common::{
-        command::command_must_succeed,
-        inference::get_stdout,
+        command::{command_must_get_stdout, command_must_succeed},
+        error::{RepoError, RepoResult},
         vcs::{vcs_or_infer, VcsKind},
     },
 };
@@ -15,7 +15,7 @@ pub struct CommitWrappedOperation {
 }
 
 impl TryFrom<&CommitOperationArgs> for CommitWrappedOperation {
-    type Error = String;
+    type Error = RepoError;
 
     fn try_from(commit_args: &CommitOperationArgs) -> Result<Self, Self::Error> {
         Ok(Self {
@@ -26,16 +26,14 @@ impl TryFrom<&CommitOperationArgs> for CommitWrappedOperation {
 }
 
 impl CommitWrappedOperation {
-    pub fn prep_commit(&self) -> Result<(), String> {
+    pub fn prep_commit(&self) -> RepoResult<()> {
         match self.commit_using {
             VcsKind::Git => {
                 let mut command = PrintableShellCommand::new("git");
                 command.args(["status", "--porcelain"]);
-                let Some(stdout) = get_stdout(command) else {
-                    return Err("Could not get `git status` output".to_owned());
-                };
+                let stdout = command_must_get_stdout(command)?;
                 if stdout.trim() != "" {
-                    return Err("`git status` is not clean.".into());
+                    return Err(RepoError::user_error("`git status` is not clean."));
                 }
                 Ok(())
             }
@@ -47,9 +45,7 @@ impl CommitWrappedOperation {
                     "@ & empty() & ~merges() & description(exact:\"\")",
                 ]);
                 command.args(["--template", "'.'"]);
-                let Some(stdout) = get_stdout(command) else {
-                    return Err("Could not get `jj log` output.".to_owned());
-                };
+                let stdout = command_must_get_stdout(command)?;
                 if stdout.trim() != "." {
                     let mut command = PrintableShellCommand::new("jj");
                     command.args(["new"]);
@@ -57,12 +53,14 @@ impl CommitWrappedOperation {
                 }
                 Ok(())
             }
-            VcsKind::Mercurial => Err("Mercurial is unsupported for this operation.".into()),
+            VcsKind::Mercurial => Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            )),
         }
     }
 
     /// Includes all changes added since a prior [CommitWrappable::prep_commit] call.
-    pub fn finalize_commit(&self, message: &str) -> Result<(), String> {
+    pub fn finalize_commit(&self, message: &str) -> RepoResult<()> {
         if !self.perform_commit {
             return Ok(());
         }
@@ -81,14 +79,13 @@ impl CommitWrappedOperation {
                 command_must_succeed(command)?;
                 Ok(())
             }
-            VcsKind::Mercurial => Err("Mercurial is unsupported for this operation.".into()),
+            VcsKind::Mercurial => Err(RepoError::user_error(
+                "Mercurial is unsupported for this operation.",
+            )),
         }
     }
 
-    pub fn perform_operation(
-        &self,
-        operation: &dyn Fn() -> Result<String, String>,
-    ) -> Result<(), String> {
+    pub fn perform_operation(&self, operation: &dyn Fn() -> RepoResult<String>) -> RepoResult<()> {
         self.prep_commit()?;
         let message = operation()?;
         self.finalize_commit(&message)?;
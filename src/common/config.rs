@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs::File,
     io::{BufReader, ErrorKind},
     path::PathBuf,
@@ -9,33 +9,66 @@ use std::{
 use schemars::JsonSchema;
 use serde::Deserialize;
 
-const CONFIG_PATH: &str = "./.config/repo.json";
+use crate::common::error::{RepoError, RepoResult};
 
-#[derive(Deserialize, Debug, JsonSchema)]
+pub(crate) const CONFIG_PATH: &str = "./.config/repo.json";
+
+#[derive(Deserialize, Debug, Default, JsonSchema)]
 pub struct Config {
+    #[serde(default)]
     pub scripts: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub registries: RegistriesConfig,
+    /// Template packs added using `repo boilerplate pack add`, by name.
+    #[serde(default, rename = "templatePacks")]
+    pub template_packs: BTreeMap<String, TemplatePackConfig>,
+}
+
+/// Registries to use instead of the ecosystem defaults.
+#[derive(Deserialize, Debug, Default, JsonSchema)]
+pub struct RegistriesConfig {
+    /// URL of an `npm`-compatible registry, e.g. a private registry or a local Verdaccio instance.
+    pub npm: Option<String>,
+    /// Name of a Cargo registry configured under `[registries]` in `.cargo/config.toml`.
+    pub cargo: Option<String>,
+    /// URL of a RubyGems-compatible host (passed to `gem push --host`).
+    pub rubygems: Option<String>,
+}
+
+/// A git repository with templates (in its `templates/` folder), pinned to a commit.
+#[derive(Deserialize, Debug, Clone, JsonSchema)]
+pub struct TemplatePackConfig {
+    /// A git URL or a local path.
+    pub source: String,
+    pub commit: String,
 }
 
 // We share one lazily loaded config for the runtime of the program.
 // TODO: parse this eagerly at startup if it's cheap enough.
-static SHARED_CONFIG: LazyLock<Config> = LazyLock::new(|| {
+static SHARED_CONFIG: LazyLock<RepoResult<Config>> = LazyLock::new(|| {
     let file = match File::open(PathBuf::from(CONFIG_PATH)) {
         Ok(file) => file,
         Err(e) => {
             if e.kind() == ErrorKind::NotFound {
-                return Config {
-                    scripts: HashMap::default(),
-                };
+                return Ok(Config::default());
             }
-            panic!("Config file is present, but could not be read.")
+            return Err(RepoError::config(format!(
+                "Config file is present, but could not be read: {}",
+                e
+            )));
         }
     };
-    serde_json::from_reader(BufReader::new(file)).unwrap()
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+        RepoError::config(format!(
+            "Could not parse config file ({}): {}",
+            CONFIG_PATH, e
+        ))
+    })
 });
 
 // TODO: singleton
 impl Config {
-    pub fn get() -> &'static Self {
-        &SHARED_CONFIG
+    pub fn get() -> RepoResult<&'static Self> {
+        SHARED_CONFIG.as_ref().map_err(Clone::clone)
     }
 }
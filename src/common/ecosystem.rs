@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
 
 #[derive(Args, Debug)]
 pub(crate) struct EcosystemArgs {
@@ -8,11 +9,16 @@ pub(crate) struct EcosystemArgs {
     pub(crate) ecosystem: Option<Ecosystem>,
 }
 
-#[derive(Debug, Copy, Clone, ValueEnum, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, ValueEnum, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub(crate) enum Ecosystem {
     #[clap(name = "javascript")]
     JavaScript,
+    // Deno/JSR packages (`deno.json`, `deno.jsonc`, or `jsr.json`).
+    Jsr,
     Rust,
+    // Ruby gems (`*.gemspec` with a `VERSION` constant in `lib/**/version.rb`).
+    Ruby,
     // Python,
 }
 
@@ -23,7 +29,9 @@ impl Display for Ecosystem {
             "{}",
             match self {
                 Ecosystem::JavaScript => "javascript",
+                Ecosystem::Jsr => "jsr",
                 Ecosystem::Rust => "rust",
+                Ecosystem::Ruby => "ruby",
                 // Ecosystem::Python => "python",
             }
         )
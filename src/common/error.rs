@@ -0,0 +1,122 @@
+use std::{fmt::Display, io, process::exit};
+
+/// The category of a failure. Each category has its own exit code, so that scripts can tell failures apart:
+///
+/// | Exit code | Kind               | Meaning                                                       |
+/// |-----------|--------------------|---------------------------------------------------------------|
+/// | 1         | `Internal`         | A bug in `repo` itself.                                       |
+/// | 2         | `UserError`        | Invalid arguments or input (matches `clap` usage errors).     |
+/// | 3         | `DetectionFailure` | Could not detect a VCS, ecosystem, package manager, etc.      |
+/// | 4         | `ToolMissing`      | A required external tool (e.g. `npm`, `cargo`) is not found.  |
+/// | 5         | `ToolFailed`       | An external tool ran but exited unsuccessfully.               |
+/// | 6         | `FileSystem`       | A file could not be read or written.                          |
+/// | 7         | `Config`           | `.config/repo.json` is invalid or could not be read.          |
+/// | 8         | `CheckFailed`      | A check found differences or conflicts that need attention.   |
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RepoErrorKind {
+    Internal,
+    UserError,
+    DetectionFailure,
+    ToolMissing,
+    ToolFailed,
+    FileSystem,
+    Config,
+    CheckFailed,
+}
+
+impl RepoErrorKind {
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            Self::Internal => 1,
+            Self::UserError => 2,
+            Self::DetectionFailure => 3,
+            Self::ToolMissing => 4,
+            Self::ToolFailed => 5,
+            Self::FileSystem => 6,
+            Self::Config => 7,
+            Self::CheckFailed => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RepoError {
+    pub(crate) kind: RepoErrorKind,
+    pub(crate) message: String,
+    /// The `stderr` of the underlying tool, if any.
+    pub(crate) stderr: Option<String>,
+}
+
+pub(crate) type RepoResult<T> = Result<T, RepoError>;
+
+impl RepoError {
+    pub(crate) fn new(kind: RepoErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            stderr: None,
+        }
+    }
+
+    pub(crate) fn internal(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::Internal, message)
+    }
+
+    pub(crate) fn user_error(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::UserError, message)
+    }
+
+    pub(crate) fn detection_failure(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::DetectionFailure, message)
+    }
+
+    pub(crate) fn tool_missing(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::ToolMissing, message)
+    }
+
+    pub(crate) fn tool_failed(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::ToolFailed, message)
+    }
+
+    pub(crate) fn file_system(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::FileSystem, message)
+    }
+
+    pub(crate) fn config(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::Config, message)
+    }
+
+    pub(crate) fn check_failed(message: impl Into<String>) -> Self {
+        Self::new(RepoErrorKind::CheckFailed, message)
+    }
+
+    pub(crate) fn with_stderr(mut self, stderr: impl Into<String>) -> Self {
+        let stderr: String = stderr.into();
+        if !stderr.trim().is_empty() {
+            self.stderr = Some(stderr);
+        }
+        self
+    }
+
+    /// Prints the error (including any `stderr` from the underlying tool) and exits with the exit code for its kind.
+    pub(crate) fn exit(&self) -> ! {
+        eprintln!("{}", self);
+        exit(self.kind.exit_code());
+    }
+}
+
+impl Display for RepoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(stderr) = &self.stderr {
+            write!(f, "\n\n{}", stderr.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl From<io::Error> for RepoError {
+    fn from(error: io::Error) -> Self {
+        Self::file_system(error.to_string())
+    }
+}
@@ -0,0 +1,50 @@
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use super::vcs::auto_detect_remote_url;
+
+#[derive(Debug, Clone, Copy, ValueEnum, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum VCSForge {
+    #[default]
+    #[clap(name = "github")]
+    GitHub,
+    Codeberg,
+    #[clap(name = "gitlab")]
+    GitLab,
+    /// Self-hosted Forgejo (using Forgejo Actions).
+    Forgejo,
+}
+
+impl VCSForge {
+    /// Infers the forge from the URL of the `origin` remote.
+    pub(crate) fn auto_detect() -> Option<Self> {
+        let remote_url = auto_detect_remote_url()?;
+        if remote_url.contains("github.com") {
+            Some(Self::GitHub)
+        } else if remote_url.contains("codeberg.org") {
+            Some(Self::Codeberg)
+        } else if remote_url.contains("gitlab.com") {
+            Some(Self::GitLab)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for VCSForge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::GitHub => "GitHub",
+                Self::Codeberg => "Codeberg",
+                Self::GitLab => "GitLab",
+                Self::Forgejo => "Forgejo",
+            }
+        )
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    env::current_dir,
+    fs::{read_to_string, write},
+};
+
+use crate::common::{
+    ecosystem::Ecosystem,
+    error::RepoResult,
+    package_manager::PackageManager,
+    vcs::{auto_detect_preferred_vcs_and_repo_root, VcsKind},
+};
+
+pub(crate) const GITIGNORE_PATH: &str = "./.gitignore";
+// Lines between these markers are overwritten by `repo boilerplate gitignore`.
+const BLOCK_START: &str = "# BEGIN repo boilerplate gitignore";
+const BLOCK_END: &str = "# END repo boilerplate gitignore";
+
+/// The contents of the managed block (without markers), composed from sections for the given ecosystems.
+pub(crate) fn gitignore_block(
+    ecosystems: &[Ecosystem],
+    javascript_package_manager: Option<PackageManager>,
+    uses_jj: bool,
+) -> String {
+    let mut sections: Vec<(&str, Vec<&str>)> = vec![("Common", vec!["/.temp/", ".DS_Store"])];
+    if ecosystems.contains(&Ecosystem::Rust) {
+        sections.push(("Rust", vec!["/target/"]));
+    }
+    if ecosystems.contains(&Ecosystem::JavaScript) {
+        let mut entries = vec!["/node_modules/", "/dist/"];
+        match javascript_package_manager {
+            Some(PackageManager::Npm) => entries.push("npm-debug.log*"),
+            Some(PackageManager::Yarn) => {
+                entries.extend([".yarn/*", "!.yarn/releases", "!.yarn/plugins", ".pnp.*"])
+            }
+            Some(PackageManager::Pnpm) => entries.push("/.pnpm-store/"),
+            _ => {}
+        }
+        sections.push(("JavaScript", entries));
+    }
+    if ecosystems.contains(&Ecosystem::Ruby) {
+        sections.push(("Ruby", vec!["/.bundle/", "/vendor/bundle/", "*.gem"]));
+    }
+    // `jj` ignores its own folder, but other tools (e.g. file watchers and formatters) that read `.gitignore` don't know about it.
+    if uses_jj {
+        sections.push(("jj", vec!["/.jj/"]));
+    }
+    sections
+        .into_iter()
+        .map(|(name, entries)| format!("# {}\n{}\n", name, entries.join("\n")))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Replaces the managed block in `source` (or appends it), leaving all other lines untouched.
+pub(crate) fn update_gitignore_block(source: &str, block: &str) -> String {
+    let managed_block = format!("{}\n{}{}\n", BLOCK_START, block, BLOCK_END);
+    let start = source.find(BLOCK_START);
+    let end = source
+        .find(BLOCK_END)
+        .map(|end| end + BLOCK_END.len())
+        .map(|end| end + usize::from(source[end..].starts_with('\n')));
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => {
+            format!("{}{}{}", &source[..start], managed_block, &source[end..])
+        }
+        _ if source.trim().is_empty() => managed_block,
+        _ => {
+            let separator = if source.ends_with("\n\n") {
+                ""
+            } else if source.ends_with('\n') {
+                "\n"
+            } else {
+                "\n\n"
+            };
+            format!("{}{}{}", source, separator, managed_block)
+        }
+    }
+}
+
+fn uses_jj() -> bool {
+    let Ok(dir) = current_dir() else {
+        return false;
+    };
+    matches!(
+        auto_detect_preferred_vcs_and_repo_root(&dir),
+        Some((VcsKind::Jj, _))
+    )
+}
+
+/// Writes the managed block to `.gitignore`. Returns whether the file changed.
+pub(crate) fn write_gitignore(ecosystems: &[Ecosystem]) -> RepoResult<bool> {
+    let block = gitignore_block(
+        ecosystems,
+        PackageManager::auto_detect_preferred_package_manager_for_ecosystem(Ecosystem::JavaScript),
+        uses_jj(),
+    );
+    let source = read_to_string(GITIGNORE_PATH).unwrap_or_default();
+    let contents = update_gitignore_block(&source, &block);
+    if contents == source {
+        return Ok(false);
+    }
+    write(GITIGNORE_PATH, contents)?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        ecosystem::Ecosystem,
+        gitignore::{gitignore_block, update_gitignore_block},
+        package_manager::PackageManager,
+    };
+
+    #[test]
+    fn test_update_gitignore_block() {
+        let block = gitignore_block(
+            &[Ecosystem::Rust, Ecosystem::JavaScript],
+            Some(PackageManager::Bun),
+            true,
+        );
+        assert_eq!(
+            block,
+            "# Common\n/.temp/\n.DS_Store\n\n# Rust\n/target/\n\n# JavaScript\n/node_modules/\n/dist/\n\n# jj\n/.jj/\n"
+        );
+        let contents = update_gitignore_block("/secrets.txt\n", "# Rust\n/target/\n");
+        assert_eq!(
+            contents,
+            "/secrets.txt\n\n# BEGIN repo boilerplate gitignore\n# Rust\n/target/\n# END repo boilerplate gitignore\n"
+        );
+        let updated = update_gitignore_block(&format!("{}/local/\n", contents), &block);
+        assert!(updated.starts_with("/secrets.txt\n\n# BEGIN"));
+        assert!(updated.contains("/.jj/\n# END repo boilerplate gitignore\n/local/\n"));
+        assert_eq!(update_gitignore_block(&updated, &block), updated);
+    }
+}
@@ -0,0 +1,323 @@
+use std::ops::Range;
+
+/// Replaces `//` and `/* … */` comments and trailing commas with whitespace, so that JSONC files (e.g. `deno.jsonc`) can be parsed using `serde_json`.
+/// Byte offsets are preserved, so positions in the output are also valid positions in the source.
+pub(crate) fn strip_jsonc(source: &str) -> String {
+    let blank = |s: &str| -> String {
+        s.chars()
+            .map(|c| {
+                if c == '\n' {
+                    "\n".to_owned()
+                } else {
+                    " ".repeat(c.len_utf8())
+                }
+            })
+            .collect()
+    };
+
+    let mut without_comments = String::with_capacity(source.len());
+    let mut in_string = false;
+    let mut i = 0;
+    while i < source.len() {
+        let rest = &source[i..];
+        let c = rest.chars().next().unwrap();
+        if in_string {
+            if c == '\\' {
+                let escaped_len = rest[1..].chars().next().map_or(0, char::len_utf8);
+                without_comments.push_str(&rest[..1 + escaped_len]);
+                i += 1 + escaped_len;
+                continue;
+            }
+            if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if rest.starts_with("//") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            without_comments.push_str(&blank(&rest[..len]));
+            i += len;
+            continue;
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            let len = comment.find("*/").map_or(rest.len(), |end| end + 4);
+            without_comments.push_str(&blank(&rest[..len]));
+            i += len;
+            continue;
+        }
+        without_comments.push(c);
+        i += c.len_utf8();
+    }
+
+    let mut stripped = String::with_capacity(without_comments.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in without_comments.char_indices() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = without_comments[i + 1..].trim_start().chars().next();
+            if matches!(next, Some('}') | Some(']')) {
+                stripped.push(' ');
+                continue;
+            }
+        }
+        stripped.push(c);
+    }
+    stripped
+}
+
+/// Returns the byte range of the contents (excluding quotes) of a string value for the given key in the top-level object.
+pub(crate) fn find_top_level_string_value(source: &str, key: &str) -> Option<Range<usize>> {
+    let stripped = strip_jsonc(source);
+    let bytes = stripped.as_bytes();
+    let string_end = |start: usize| -> Option<usize> {
+        let mut i = start + 1;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'\\' => i += 2,
+                b'"' => return Some(i),
+                _ => i += 1,
+            }
+        }
+        None
+    };
+    let skip_whitespace = |mut i: usize| -> usize {
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+        i
+    };
+
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            b'"' => {
+                let end = string_end(i)?;
+                let after_string = skip_whitespace(end + 1);
+                let is_key = bytes.get(after_string) == Some(&b':');
+                if depth == 1 && is_key && &stripped[i + 1..end] == key {
+                    let value_start = skip_whitespace(after_string + 1);
+                    if bytes.get(value_start) != Some(&b'"') {
+                        return None;
+                    }
+                    return Some(value_start + 1..string_end(value_start)?);
+                }
+                i = end;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Sets a string value for the given key in the top-level object, preserving all other formatting.
+/// Returns `None` if the key is not present.
+pub(crate) fn set_top_level_string_value(source: &str, key: &str, value: &str) -> Option<String> {
+    let range = find_top_level_string_value(source, key)?;
+    let escaped = serde_json::to_string(value).ok()?;
+    Some(format!(
+        "{}{}{}",
+        &source[..range.start],
+        &escaped[1..escaped.len() - 1],
+        &source[range.end..]
+    ))
+}
+
+/// A parsed JSONC value, with byte ranges into the source (comments are not represented).
+#[derive(Debug)]
+pub(crate) enum JsoncNode {
+    Object {
+        range: Range<usize>,
+        members: Vec<JsoncMember>,
+    },
+    Array {
+        range: Range<usize>,
+        elements: Vec<JsoncNode>,
+    },
+    Scalar {
+        range: Range<usize>,
+    },
+}
+
+#[derive(Debug)]
+pub(crate) struct JsoncMember {
+    pub(crate) key: String,
+    /// Includes the quotes.
+    pub(crate) key_range: Range<usize>,
+    pub(crate) value: JsoncNode,
+}
+
+impl JsoncNode {
+    pub(crate) fn range(&self) -> Range<usize> {
+        match self {
+            Self::Object { range, .. } | Self::Array { range, .. } | Self::Scalar { range } => {
+                range.clone()
+            }
+        }
+    }
+}
+
+struct JsoncParser<'a> {
+    stripped: &'a [u8],
+    i: usize,
+}
+
+impl JsoncParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.i < self.stripped.len() && self.stripped[self.i].is_ascii_whitespace() {
+            self.i += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        self.skip_whitespace();
+        if self.stripped.get(self.i) != Some(&byte) {
+            return None;
+        }
+        self.i += 1;
+        Some(())
+    }
+
+    fn string(&mut self) -> Option<Range<usize>> {
+        let start = self.i;
+        self.i += 1;
+        while self.i < self.stripped.len() {
+            match self.stripped[self.i] {
+                b'\\' => self.i += 2,
+                b'"' => {
+                    self.i += 1;
+                    return Some(start..self.i);
+                }
+                _ => self.i += 1,
+            }
+        }
+        None
+    }
+
+    fn value(&mut self) -> Option<JsoncNode> {
+        self.skip_whitespace();
+        let start = self.i;
+        match self.stripped.get(self.i)? {
+            b'{' => {
+                self.i += 1;
+                let mut members = vec![];
+                loop {
+                    self.skip_whitespace();
+                    match self.stripped.get(self.i)? {
+                        b'}' => break,
+                        b',' => self.i += 1,
+                        b'"' => {
+                            let key_range = self.string()?;
+                            let key =
+                                serde_json::from_slice(&self.stripped[key_range.clone()]).ok()?;
+                            self.expect(b':')?;
+                            let value = self.value()?;
+                            members.push(JsoncMember {
+                                key,
+                                key_range,
+                                value,
+                            });
+                        }
+                        _ => return None,
+                    }
+                }
+                self.i += 1;
+                Some(JsoncNode::Object {
+                    range: start..self.i,
+                    members,
+                })
+            }
+            b'[' => {
+                self.i += 1;
+                let mut elements = vec![];
+                loop {
+                    self.skip_whitespace();
+                    match self.stripped.get(self.i)? {
+                        b']' => break,
+                        b',' => self.i += 1,
+                        _ => elements.push(self.value()?),
+                    }
+                }
+                self.i += 1;
+                Some(JsoncNode::Array {
+                    range: start..self.i,
+                    elements,
+                })
+            }
+            b'"' => Some(JsoncNode::Scalar {
+                range: self.string()?,
+            }),
+            _ => {
+                while self.i < self.stripped.len()
+                    && !self.stripped[self.i].is_ascii_whitespace()
+                    && !b",}]".contains(&self.stripped[self.i])
+                {
+                    self.i += 1;
+                }
+                if self.i == start {
+                    return None;
+                }
+                Some(JsoncNode::Scalar {
+                    range: start..self.i,
+                })
+            }
+        }
+    }
+}
+
+/// Parses JSONC into a tree of nodes with byte ranges that are valid in `source`.
+pub(crate) fn parse_jsonc(source: &str) -> Option<JsoncNode> {
+    let stripped = strip_jsonc(source);
+    let mut parser = JsoncParser {
+        stripped: stripped.as_bytes(),
+        i: 0,
+    };
+    let node = parser.value()?;
+    parser.skip_whitespace();
+    if parser.i != stripped.len() {
+        return None;
+    }
+    Some(node)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::jsonc::{set_top_level_string_value, strip_jsonc};
+
+    const DENO_JSONC: &str = r#"{
+  // "version": "0.0.0"
+  "imports": { "version": "npm:version" },
+  /* trailing */ "version": "1.2.3",
+  "exports": "./mod.ts",
+}
+"#;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let stripped = strip_jsonc(DENO_JSONC);
+        assert_eq!(stripped.len(), DENO_JSONC.len());
+        let value: serde_json::Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["version"], "1.2.3");
+    }
+
+    #[test]
+    fn test_set_top_level_string_value() {
+        assert_eq!(
+            set_top_level_string_value(DENO_JSONC, "version", "2.0.0").unwrap(),
+            DENO_JSONC.replace("\"1.2.3\"", "\"2.0.0\"")
+        );
+        assert_eq!(set_top_level_string_value(DENO_JSONC, "name", "x"), None);
+    }
+}
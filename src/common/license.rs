@@ -0,0 +1,351 @@
+use std::{
+    fs::{exists, read_to_string, write},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use clap::ValueEnum;
+use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
+use toml_edit::{value, DocumentMut};
+
+use crate::common::{
+    error::{RepoError, RepoResult},
+    inference::get_stdout,
+    package_manager::PACKAGE_JSON_PATH,
+    structured_merge::{merge_jsonc, ConflictMergePolicy, MergePolicies},
+    template::CARGO_TOML_PATH,
+};
+
+pub(crate) const LICENSE_PATH: &str = "./LICENSE";
+const YEAR_PLACEHOLDER: &str = "[year]";
+const HOLDER_PLACEHOLDER: &str = "[fullname]";
+const MISSING_HOLDER_MESSAGE: &str = "Could not determine the copyright holder from `LICENSE`, `git`, or `jj`. Set `user.name` in `git` or `jj`, or pass `--holder` to `repo boilerplate license add`.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum SpdxLicense {
+    #[clap(name = "MIT")]
+    Mit,
+    #[clap(name = "Apache-2.0")]
+    Apache2,
+    #[clap(name = "MPL-2.0")]
+    Mpl2,
+    #[clap(name = "ISC")]
+    Isc,
+    #[clap(name = "BSD-2-Clause")]
+    Bsd2Clause,
+    #[clap(name = "BSD-3-Clause")]
+    Bsd3Clause,
+    #[clap(name = "0BSD")]
+    ZeroBsd,
+}
+
+impl SpdxLicense {
+    pub(crate) fn all() -> [Self; 7] {
+        [
+            Self::Mit,
+            Self::Apache2,
+            Self::Mpl2,
+            Self::Isc,
+            Self::Bsd2Clause,
+            Self::Bsd3Clause,
+            Self::ZeroBsd,
+        ]
+    }
+
+    pub(crate) fn spdx_id(&self) -> &'static str {
+        match self {
+            Self::Mit => "MIT",
+            Self::Apache2 => "Apache-2.0",
+            Self::Mpl2 => "MPL-2.0",
+            Self::Isc => "ISC",
+            Self::Bsd2Clause => "BSD-2-Clause",
+            Self::Bsd3Clause => "BSD-3-Clause",
+            Self::ZeroBsd => "0BSD",
+        }
+    }
+
+    pub(crate) fn from_spdx_id(spdx_id: &str) -> Option<Self> {
+        Self::all()
+            .into_iter()
+            .find(|license| license.spdx_id() == spdx_id)
+    }
+
+    /// The license text, with `[year]` and `[fullname]` placeholders for licenses that have a copyright line.
+    fn text_template(&self) -> &'static str {
+        match self {
+            Self::Mit => include_str!("../templates/licenses/MIT.txt"),
+            Self::Apache2 => include_str!("../templates/licenses/Apache-2.0.txt"),
+            Self::Mpl2 => include_str!("../templates/licenses/MPL-2.0.txt"),
+            Self::Isc => include_str!("../templates/licenses/ISC.txt"),
+            Self::Bsd2Clause => include_str!("../templates/licenses/BSD-2-Clause.txt"),
+            Self::Bsd3Clause => include_str!("../templates/licenses/BSD-3-Clause.txt"),
+            Self::ZeroBsd => include_str!("../templates/licenses/0BSD.txt"),
+        }
+    }
+
+    fn has_copyright_line(&self) -> bool {
+        self.text_template().contains(HOLDER_PLACEHOLDER)
+    }
+
+    pub(crate) fn text(&self, copyright: &Copyright) -> String {
+        self.text_template()
+            .replace(YEAR_PLACEHOLDER, &copyright.year)
+            .replace(HOLDER_PLACEHOLDER, &copyright.holder)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Copyright {
+    /// Can also be a range, e.g. `2019-2025`.
+    pub(crate) year: String,
+    pub(crate) holder: String,
+}
+
+/// Converts days since the Unix epoch to a year in the proleptic Gregorian calendar.
+/// Based on `civil_from_days` from <https://howardhinnant.github.io/date_algorithms.html>.
+fn year_from_unix_days(days: i64) -> i64 {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    // Months are counted from March, so that the leap day is at the end of the year.
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let year = year_of_era + era * 400;
+    if month_from_march >= 10 {
+        year + 1
+    } else {
+        year
+    }
+}
+
+fn current_year() -> i64 {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() / 86400)
+        .unwrap_or_default();
+    year_from_unix_days(days as i64)
+}
+
+/// Uses `git config user.name`, falling back to the `jj` user.
+fn detect_holder() -> Option<String> {
+    let mut git_command = PrintableShellCommand::new("git");
+    git_command.args(["config", "user.name"]);
+    if let Some(name) = get_stdout(git_command).filter(|name| !name.is_empty()) {
+        return Some(name);
+    }
+    let mut jj_command = PrintableShellCommand::new("jj");
+    jj_command.args(["config", "get", "user.name"]);
+    get_stdout(jj_command).filter(|name| !name.is_empty())
+}
+
+/// The copyright line of the existing `LICENSE` file, if any.
+fn existing_copyright() -> Option<Copyright> {
+    let license = read_to_string(LICENSE_PATH).ok()?;
+    let copyright_line = static_regex!(r"(?mi)^Copyright \(c\) (\d{4}(?:\s*-\s*\d{4})?),? (.+)$");
+    let captures = copyright_line.captures(&license)?;
+    Some(Copyright {
+        year: captures[1].to_owned(),
+        holder: captures[2].trim().to_owned(),
+    })
+}
+
+/// Uses the given values, falling back to those in the existing `LICENSE` file, and then to the current year and VCS user.
+/// Nothing is detected for licenses without a copyright line (e.g. `Apache-2.0`).
+pub(crate) fn copyright_or_detect(
+    license: SpdxLicense,
+    year: Option<String>,
+    holder: Option<String>,
+) -> RepoResult<Copyright> {
+    if !license.has_copyright_line() {
+        return Ok(Copyright {
+            year: String::new(),
+            holder: String::new(),
+        });
+    }
+    let existing = existing_copyright();
+    let year = year
+        .or_else(|| existing.as_ref().map(|copyright| copyright.year.clone()))
+        .unwrap_or_else(|| current_year().to_string());
+    let Some(holder) = holder
+        .or_else(|| existing.map(|copyright| copyright.holder))
+        .or_else(detect_holder)
+    else {
+        return Err(RepoError::detection_failure(MISSING_HOLDER_MESSAGE));
+    };
+    Ok(Copyright { year, holder })
+}
+
+/// Like `copyright_or_detect(license, None, None)`, but only detected once per run (listing license templates would otherwise spawn `git`/`jj` for each license).
+pub(crate) fn detected_copyright(license: SpdxLicense) -> RepoResult<Copyright> {
+    static DETECTED_COPYRIGHT: OnceLock<Option<Copyright>> = OnceLock::new();
+    if !license.has_copyright_line() {
+        return copyright_or_detect(license, None, None);
+    }
+    DETECTED_COPYRIGHT
+        .get_or_init(|| copyright_or_detect(license, None, None).ok())
+        .clone()
+        .ok_or_else(|| RepoError::detection_failure(MISSING_HOLDER_MESSAGE))
+}
+
+/// The `license` field of `Cargo.toml` or `package.json` (an SPDX expression), if any.
+pub(crate) fn manifest_license_expression() -> Option<String> {
+    if let Ok(cargo_toml) = read_to_string(CARGO_TOML_PATH) {
+        if let Ok(document) = cargo_toml.parse::<DocumentMut>() {
+            if let Some(license) = document
+                .get("package")
+                .and_then(|package| package.get("license"))
+                .and_then(|license| license.as_str())
+            {
+                return Some(license.to_owned());
+            }
+        }
+    }
+    let package_json = read_to_string(PACKAGE_JSON_PATH).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&package_json).ok()?;
+    value["license"].as_str().map(str::to_owned)
+}
+
+/// The license from the `license` field of `Cargo.toml` or `package.json`, if it is a single supported SPDX ID.
+pub(crate) fn detect_manifest_license() -> Option<SpdxLicense> {
+    SpdxLicense::from_spdx_id(&manifest_license_expression()?)
+}
+
+/// Returns `None` if there is nothing to update.
+fn cargo_toml_with_license(source: &str, license: SpdxLicense) -> RepoResult<Option<String>> {
+    let mut document: DocumentMut = source
+        .parse()
+        .map_err(|e| RepoError::user_error(format!("`Cargo.toml` is not valid TOML: {}", e)))?;
+    // Workspace roots without a `[package]` don't have a license.
+    let Some(package) = document
+        .get_mut("package")
+        .and_then(|package| package.as_table_like_mut())
+    else {
+        return Ok(None);
+    };
+    let current = match package.get("license") {
+        Some(current) => match current.as_str() {
+            Some(current) => Some(current.to_owned()),
+            None => {
+                // e.g. `license.workspace = true`, which should stay inherited.
+                eprintln!(
+                    "⚠️ `Cargo.toml` license is not a string (e.g. inherited from the workspace), not updating it to: {}",
+                    license.spdx_id()
+                );
+                return Ok(None);
+            }
+        },
+        None => None,
+    };
+    if current.as_deref() == Some(license.spdx_id()) {
+        eprintln!(
+            "`Cargo.toml` license already matches: {}",
+            license.spdx_id()
+        );
+        return Ok(None);
+    }
+    package.insert("license", value(license.spdx_id()));
+    eprintln!(
+        "Updated `Cargo.toml` license: {} → {}",
+        current.as_deref().unwrap_or("(none)"),
+        license.spdx_id()
+    );
+    Ok(Some(document.to_string()))
+}
+
+fn update_cargo_toml_license(license: SpdxLicense) -> RepoResult<()> {
+    let Ok(source) = read_to_string(CARGO_TOML_PATH) else {
+        return Ok(());
+    };
+    if let Some(contents) = cargo_toml_with_license(&source, license)? {
+        write(CARGO_TOML_PATH, contents)?;
+    }
+    Ok(())
+}
+
+fn update_package_json_license(license: SpdxLicense) -> RepoResult<()> {
+    if !exists(PACKAGE_JSON_PATH)? {
+        return Ok(());
+    }
+    let source = read_to_string(PACKAGE_JSON_PATH)?;
+    let current = serde_json::from_str::<serde_json::Value>(&source)
+        .ok()
+        .and_then(|value| value["license"].as_str().map(str::to_owned));
+    if current.as_deref() == Some(license.spdx_id()) {
+        eprintln!(
+            "`package.json` license already matches: {}",
+            license.spdx_id()
+        );
+        return Ok(());
+    }
+    let template = serde_json::json!({ "license": license.spdx_id() }).to_string();
+    let contents = merge_jsonc(
+        &source,
+        &template,
+        MergePolicies {
+            conflict: ConflictMergePolicy::Template,
+            ..MergePolicies::default()
+        },
+    )?;
+    write(PACKAGE_JSON_PATH, contents)?;
+    eprintln!(
+        "Updated `package.json` license: {} → {}",
+        current.as_deref().unwrap_or("(none)"),
+        license.spdx_id()
+    );
+    Ok(())
+}
+
+/// Sets the `license` field in `Cargo.toml` and `package.json` (if present) to match.
+pub(crate) fn update_manifest_licenses(license: SpdxLicense) -> RepoResult<()> {
+    update_cargo_toml_license(license)?;
+    update_package_json_license(license)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::license::{
+        cargo_toml_with_license, year_from_unix_days, Copyright, SpdxLicense,
+    };
+
+    #[test]
+    fn test_license_text() {
+        assert_eq!(year_from_unix_days(0), 1970);
+        assert_eq!(year_from_unix_days(19782), 2024); // 2024-02-29
+        assert_eq!(year_from_unix_days(20088), 2024); // 2024-12-31
+        assert_eq!(year_from_unix_days(20089), 2025); // 2025-01-01
+        let copyright = Copyright {
+            year: "2025".to_owned(),
+            holder: "Alex Example".to_owned(),
+        };
+        assert!(SpdxLicense::Mit
+            .text(&copyright)
+            .contains("Copyright (c) 2025 Alex Example\n"));
+        assert!(SpdxLicense::Bsd3Clause
+            .text(&copyright)
+            .contains("Copyright (c) 2025, Alex Example\n"));
+    }
+
+    #[test]
+    fn test_cargo_toml_with_license() {
+        assert_eq!(
+            cargo_toml_with_license(
+                "[package]\nname = \"example\"\nlicense = \"Apache-2.0\"\n",
+                SpdxLicense::Mit
+            )
+            .unwrap()
+            .as_deref(),
+            Some("[package]\nname = \"example\"\nlicense = \"MIT\"\n")
+        );
+        assert_eq!(
+            cargo_toml_with_license(
+                "[package]\nname = \"example\"\nlicense.workspace = true\n",
+                SpdxLicense::Mit
+            )
+            .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    fs::{read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
+
+use crate::common::{
+    error::{RepoError, RepoResult},
+    inference::get_stdout,
+    license::manifest_license_expression,
+};
+
+const SPDX_PREFIX: &str = "SPDX-License-Identifier:";
+/// Headers are only looked for in the first few lines (after a shebang, a header usually comes first).
+const HEADER_SEARCH_LINES: usize = 5;
+/// Files with this marker near the top are generated, and are skipped.
+const GENERATED_MARKER: &str = "@generated";
+
+/// The line comment syntax for supported file types.
+fn comment_prefix(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "rs" | "ts" | "tsx" | "mts" | "cts" | "js" | "jsx" | "mjs" | "cjs" => Some("//"),
+        _ => None,
+    }
+}
+
+/// Tracked and untracked files that are not ignored by the VCS (using `git`, which also works for colocated `jj` repos, or `jj`).
+fn unignored_files() -> RepoResult<Vec<PathBuf>> {
+    let mut git_command = PrintableShellCommand::new("git");
+    git_command.args(["ls-files", "--cached", "--others", "--exclude-standard"]);
+    let files = match get_stdout(git_command) {
+        Some(files) => files,
+        None => {
+            let mut jj_command = PrintableShellCommand::new("jj");
+            jj_command.args(["file", "list"]);
+            get_stdout(jj_command).ok_or_else(|| {
+                RepoError::detection_failure(
+                    "Could not list files using `git` or `jj` (license headers are only managed in repositories).",
+                )
+            })?
+        }
+    };
+    Ok(files.lines().map(PathBuf::from).collect())
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum HeaderStatus {
+    Present,
+    Missing,
+    Mismatched(String),
+    Generated,
+}
+
+fn header_status(source: &str, license: &str) -> HeaderStatus {
+    let head: Vec<&str> = source.lines().take(HEADER_SEARCH_LINES).collect();
+    if head.iter().any(|line| line.contains(GENERATED_MARKER)) {
+        return HeaderStatus::Generated;
+    }
+    let spdx_line = static_regex!(r"SPDX-License-Identifier:\s*(.+?)\s*(?:\*/)?$");
+    match head.iter().find_map(|line| {
+        spdx_line
+            .captures(line)
+            .map(|captures| captures[1].to_owned())
+    }) {
+        Some(existing) if existing == license => HeaderStatus::Present,
+        Some(existing) => HeaderStatus::Mismatched(existing),
+        None => HeaderStatus::Missing,
+    }
+}
+
+/// Rust inner attributes (`#![…]`) also start with `#!`, but belong after the header.
+fn is_shebang(source: &str) -> bool {
+    source.starts_with("#!") && !source.starts_with("#![")
+}
+
+/// Inserts the header (after a shebang line, if any), or replaces an existing header for a different license.
+fn with_license_header(source: &str, comment_prefix: &str, license: &str) -> String {
+    let header = format!("{} {} {}", comment_prefix, SPDX_PREFIX, license);
+    let mut lines: Vec<&str> = source.split_inclusive('\n').collect();
+    if let Some(index) = lines
+        .iter()
+        .take(HEADER_SEARCH_LINES)
+        .position(|line| line.contains(SPDX_PREFIX))
+    {
+        let replacement = format!("{}\n", header);
+        lines[index] = &replacement;
+        return lines.concat();
+    }
+    let insert_at = usize::from(is_shebang(source));
+    let shebang_line;
+    if insert_at == 1 && !lines[0].ends_with('\n') {
+        shebang_line = format!("{}\n", lines[0]);
+        lines[0] = &shebang_line;
+    }
+    let separator = match lines.get(insert_at) {
+        Some(line) if line.trim().is_empty() => "",
+        Some(_) => "\n",
+        None => "",
+    };
+    let inserted = format!("{}\n{}", header, separator);
+    lines.insert(insert_at, &inserted);
+    lines.concat()
+}
+
+/// Checks (or with `apply`, inserts) SPDX license headers in the given files, or all supported files that are not ignored by the VCS.
+pub(crate) fn license_headers(
+    paths: Vec<PathBuf>,
+    license: Option<String>,
+    apply: bool,
+) -> RepoResult<()> {
+    let Some(license) = license.or_else(manifest_license_expression) else {
+        return Err(RepoError::detection_failure(
+            "Could not determine the license from `Cargo.toml` or `package.json`. Pass `--license`.",
+        ));
+    };
+    let paths = if paths.is_empty() {
+        unignored_files()?
+    } else {
+        paths
+    };
+    let mut num_checked = 0;
+    let mut num_problems = 0;
+    for path in paths {
+        let Some(comment_prefix) = comment_prefix(&path) else {
+            continue;
+        };
+        // Files can be listed by the VCS but deleted in the working copy.
+        let Ok(source) = read_to_string(&path) else {
+            continue;
+        };
+        let status = header_status(&source, &license);
+        let path_string = path.to_string_lossy();
+        match &status {
+            HeaderStatus::Present => {}
+            HeaderStatus::Generated => continue,
+            HeaderStatus::Missing if apply => println!("Added header: {}", path_string),
+            HeaderStatus::Mismatched(existing) if apply => println!(
+                "Updated header ({} → {}): {}",
+                existing, license, path_string
+            ),
+            HeaderStatus::Missing => println!("❌ {} is missing a license header.", path_string),
+            HeaderStatus::Mismatched(existing) => println!(
+                "❌ {} has a license header for `{}` instead of `{}`.",
+                path_string, existing, license
+            ),
+        }
+        num_checked += 1;
+        if status == HeaderStatus::Present {
+            continue;
+        }
+        num_problems += 1;
+        if apply {
+            write(
+                &path,
+                with_license_header(&source, comment_prefix, &license),
+            )?;
+        }
+    }
+    if apply {
+        println!(
+            "{} of {} file(s) updated with `{} {}`.",
+            num_problems, num_checked, SPDX_PREFIX, license
+        );
+        return Ok(());
+    }
+    if num_problems > 0 {
+        return Err(RepoError::check_failed(format!(
+            "{} of {} file(s) need a license header (run `repo boilerplate license-headers apply`).",
+            num_problems, num_checked
+        )));
+    }
+    println!(
+        "✅ All {} file(s) have `{} {}`.",
+        num_checked, SPDX_PREFIX, license
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::license_headers::{header_status, with_license_header, HeaderStatus};
+
+    #[test]
+    fn test_with_license_header() {
+        assert_eq!(
+            header_status("fn main() {}\n", "MIT"),
+            HeaderStatus::Missing
+        );
+        assert_eq!(
+            with_license_header("fn main() {}\n", "//", "MIT"),
+            "// SPDX-License-Identifier: MIT\n\nfn main() {}\n"
+        );
+        let script = "#!/usr/bin/env -S bun run --\n\nconsole.log(1);\n";
+        let with_header = with_license_header(script, "//", "MIT");
+        assert_eq!(
+            with_header,
+            "#!/usr/bin/env -S bun run --\n// SPDX-License-Identifier: MIT\n\nconsole.log(1);\n"
+        );
+        assert_eq!(header_status(&with_header, "MIT"), HeaderStatus::Present);
+        assert_eq!(
+            header_status(&with_header, "MPL-2.0"),
+            HeaderStatus::Mismatched("MIT".to_owned())
+        );
+        assert_eq!(
+            with_license_header(&with_header, "//", "MPL-2.0"),
+            with_header.replace("MIT", "MPL-2.0")
+        );
+        assert_eq!(
+            header_status("// @generated by build.rs\n", "MIT"),
+            HeaderStatus::Generated
+        );
+        assert_eq!(
+            with_license_header("#![deny(warnings)]\n\nfn main() {}\n", "//", "MIT"),
+            "// SPDX-License-Identifier: MIT\n\n#![deny(warnings)]\n\nfn main() {}\n"
+        );
+        assert_eq!(
+            with_license_header("", "//", "MIT"),
+            "// SPDX-License-Identifier: MIT\n"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use regex_static::static_regex;
+
+pub(crate) const MAKEFILE_PATH: &str = "./Makefile";
+
+pub(crate) struct MakefileTarget<'a> {
+    pub(crate) name: &'a str,
+    /// An aggregate target (e.g. `lint`) that should depend on this target.
+    pub(crate) aggregate: Option<&'a str>,
+    pub(crate) recipe: Vec<String>,
+}
+
+/// Returns the target names of a rule line (e.g. `lint lint-all: setup` → `["lint", "lint-all"]`).
+fn rule_names(line: &str) -> Option<Vec<&str>> {
+    let rule = static_regex!(r"^([^\s:#=][^:#=]*?)\s*::?(?:[^=]|$)");
+    let captures = rule.captures(line)?;
+    Some(captures.get(1)?.as_str().split_whitespace().collect())
+}
+
+fn phony_names(line: &str) -> Option<Vec<&str>> {
+    Some(line.strip_prefix(".PHONY:")?.split_whitespace().collect())
+}
+
+fn is_rule_for(line: &str, name: &str) -> bool {
+    rule_names(line).is_some_and(|names| names.first() != Some(&".PHONY") && names.contains(&name))
+}
+
+fn rule_line_index(lines: &[String], name: &str) -> Option<usize> {
+    lines.iter().position(|line| is_rule_for(line, name))
+}
+
+pub(crate) fn has_makefile_target(source: &str, name: &str) -> bool {
+    source.lines().any(|line| is_rule_for(line, name))
+}
+
+/// Whether `.PHONY` is declared right before each target (as opposed to a single shared `.PHONY` list).
+fn uses_per_target_phony(lines: &[String]) -> bool {
+    let mut phony_lines = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| phony_names(line).map(|names| (i, names)))
+        .peekable();
+    if phony_lines.peek().is_none() {
+        return true;
+    }
+    phony_lines.any(|(i, names)| {
+        names.len() == 1
+            && lines
+                .get(i + 1)
+                .and_then(|line| rule_names(line))
+                .is_some_and(|rule_names| rule_names.contains(&names[0]))
+    })
+}
+
+fn add_rule(lines: &mut Vec<String>, name: &str, prerequisites: &str, recipe: &[String]) {
+    let per_target_phony = uses_per_target_phony(lines);
+    if !per_target_phony {
+        if let Some(phony_line) = lines.iter_mut().find(|line| line.starts_with(".PHONY:")) {
+            phony_line.push(' ');
+            phony_line.push_str(name);
+        }
+    }
+    if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+        lines.push(String::new());
+    }
+    if per_target_phony {
+        lines.push(format!(".PHONY: {}", name));
+    }
+    if prerequisites.is_empty() {
+        lines.push(format!("{}:", name));
+    } else {
+        lines.push(format!("{}: {}", name, prerequisites));
+    }
+    for recipe_line in recipe {
+        lines.push(format!("\t{}", recipe_line));
+    }
+}
+
+/// Adds the given targets to a `Makefile`, and adds them as prerequisites of their aggregate targets (creating those if needed).
+/// Existing targets are left untouched, so this is idempotent.
+/// Returns the new contents and the names of the targets that were added.
+pub(crate) fn add_makefile_targets(
+    source: &str,
+    targets: &[MakefileTarget],
+) -> (String, Vec<String>) {
+    let mut lines: Vec<String> = source.lines().map(str::to_owned).collect();
+    let mut added = vec![];
+    for target in targets {
+        if rule_line_index(&lines, target.name).is_none() {
+            add_rule(&mut lines, target.name, "", &target.recipe);
+            added.push(target.name.to_owned());
+        }
+        let Some(aggregate) = target.aggregate else {
+            continue;
+        };
+        match rule_line_index(&lines, aggregate) {
+            Some(i) => {
+                let line = &lines[i];
+                // Insert before any inline recipe (`;`) or comment (`#`).
+                let end = line.find([';', '#']).unwrap_or(line.len());
+                let (_, prerequisites) = line[..end].split_once(':').unwrap_or_default();
+                if !prerequisites
+                    .split_whitespace()
+                    .any(|name| name == target.name)
+                {
+                    let (rule, rest) = line.split_at(end);
+                    let trailing_space = if rest.is_empty() { "" } else { " " };
+                    lines[i] = format!(
+                        "{} {}{}{}",
+                        rule.trim_end(),
+                        target.name,
+                        trailing_space,
+                        rest
+                    );
+                }
+            }
+            None => {
+                add_rule(&mut lines, aggregate, target.name, &[]);
+                added.push(aggregate.to_owned());
+            }
+        }
+    }
+    let mut contents = lines.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    (contents, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::makefile::{add_makefile_targets, MakefileTarget};
+
+    #[test]
+    fn test_add_makefile_targets() {
+        let targets = [
+            MakefileTarget {
+                name: "lint-rust",
+                aggregate: Some("lint"),
+                recipe: vec!["cargo clippy".to_owned()],
+            },
+            MakefileTarget {
+                name: "format-rust",
+                aggregate: Some("format"),
+                recipe: vec!["cargo fmt".to_owned()],
+            },
+        ];
+        let source = ".PHONY: lint\nlint: lint-biome # all linting\n";
+        let (contents, added) = add_makefile_targets(source, &targets);
+        assert_eq!(
+            contents,
+            ".PHONY: lint
+lint: lint-biome lint-rust # all linting
+
+.PHONY: lint-rust
+lint-rust:
+\tcargo clippy
+
+.PHONY: format-rust
+format-rust:
+\tcargo fmt
+
+.PHONY: format
+format: format-rust
+"
+        );
+        assert_eq!(added, vec!["lint-rust", "format-rust", "format"]);
+        assert_eq!(
+            add_makefile_targets(&contents, &targets),
+            (contents, vec![])
+        );
+
+        let (contents, _) = add_makefile_targets(
+            ".PHONY: build test\n\nbuild:\n\tcargo build\n",
+            &targets[..1],
+        );
+        assert_eq!(
+            contents,
+            ".PHONY: build test lint-rust lint\n\nbuild:\n\tcargo build\n\nlint-rust:\n\tcargo clippy\n\nlint: lint-rust\n"
+        );
+    }
+}
@@ -1,11 +1,29 @@
+pub(crate) mod action_pins;
 pub(crate) mod args;
+pub(crate) mod boilerplate_lockfile;
+pub(crate) mod cargo_toml;
+pub(crate) mod ci;
 pub(crate) mod command;
 pub(crate) mod commit_wrapped_operation;
 pub(crate) mod config;
 pub(crate) mod debug;
 pub(crate) mod ecosystem;
+pub(crate) mod error;
+pub(crate) mod forge;
+pub(crate) mod gitignore;
 pub(crate) mod inference;
+pub(crate) mod jsonc;
+pub(crate) mod license;
+pub(crate) mod license_headers;
+pub(crate) mod makefile;
+pub(crate) mod package_json;
 pub(crate) mod package_manager;
+pub(crate) mod readme_cli_help;
+pub(crate) mod registry;
+pub(crate) mod structured_merge;
+pub(crate) mod template;
+pub(crate) mod template_bundle;
 pub(crate) mod template_file;
+pub(crate) mod template_pack;
 pub(crate) mod vcs;
 pub(crate) mod workspace;
@@ -0,0 +1,152 @@
+use std::fs::{exists, read_to_string, write};
+
+use printable_shell_command::PrintableShellCommand;
+use serde_json::Value;
+
+use crate::common::{
+    cargo_toml::repository_url_from_remote,
+    ecosystem::Ecosystem,
+    error::{RepoError, RepoResult},
+    inference::get_stdout,
+    package_manager::{PackageManager, PACKAGE_JSON_PATH},
+    structured_merge::{merge_jsonc, ArrayMergePolicy, ConflictMergePolicy, MergePolicies},
+    template::detect_project_name,
+    vcs::auto_detect_remote_url,
+};
+
+const INITIAL_VERSION: &str = "0.1.0";
+// Build output, matching the `exclude` in the `tsconfig.json` templates.
+const ENTRY_POINT: &str = "./dist/index.js";
+const TYPES_ENTRY_POINT: &str = "./dist/index.d.ts";
+const FILES: &str = "./dist/";
+
+pub(crate) struct PackageJsonFields {
+    pub(crate) name: Option<String>,
+    pub(crate) package_manager: Option<PackageManager>,
+}
+
+/// The `packageManager` value (e.g. `bun@1.2.3`) for the installed version of a JS package manager.
+fn package_manager_pin(package_manager: &PackageManager) -> RepoResult<Option<String>> {
+    if !matches!(
+        package_manager,
+        PackageManager::Npm | PackageManager::Bun | PackageManager::Yarn | PackageManager::Pnpm
+    ) {
+        return Err(RepoError::user_error(format!(
+            "Not a JS package manager: {}",
+            package_manager
+        )));
+    }
+    let mut command = PrintableShellCommand::new(package_manager.to_string());
+    command.arg("--version");
+    Ok(get_stdout(command).map(|version| format!("{}@{}", package_manager, version.trim())))
+}
+
+/// Built by hand (rather than using `json!(…)`) to keep the conventional key order.
+fn package_json_template(
+    name: &str,
+    repository: Option<&str>,
+    package_manager_pin: Option<&str>,
+) -> String {
+    let string = |value: &str| Value::from(value).to_string();
+    let mut entries = vec![
+        format!("  \"name\": {}", string(name)),
+        format!("  \"version\": {}", string(INITIAL_VERSION)),
+        "  \"type\": \"module\"".to_owned(),
+        format!(
+            "  \"exports\": {{\n    \".\": {{\n      \"types\": {},\n      \"default\": {}\n    }}\n  }}",
+            string(TYPES_ENTRY_POINT),
+            string(ENTRY_POINT)
+        ),
+        format!("  \"types\": {}", string(TYPES_ENTRY_POINT)),
+        format!("  \"files\": [{}]", string(FILES)),
+    ];
+    if let Some(repository) = repository {
+        entries.push(format!("  \"repository\": {}", string(repository)));
+    }
+    if let Some(package_manager_pin) = package_manager_pin {
+        entries.push(format!(
+            "  \"packageManager\": {}",
+            string(package_manager_pin)
+        ));
+    }
+    format!("{{\n{}\n}}\n", entries.join(",\n"))
+}
+
+/// Creates `package.json`, or adds any missing fields to an existing one (keeping all existing values).
+pub(crate) fn update_package_json(fields: PackageJsonFields) -> RepoResult<()> {
+    let Some(name) = fields.name.or_else(detect_project_name) else {
+        return Err(RepoError::detection_failure(
+            "Could not determine the package name (pass `--name`).",
+        ));
+    };
+    let repository = auto_detect_remote_url().and_then(|url| repository_url_from_remote(&url));
+    if repository.is_none() {
+        eprintln!("⚠️ Could not determine `repository` from the `origin` remote.");
+    }
+    let package_manager = fields.package_manager.unwrap_or_else(|| {
+        PackageManager::auto_detect_preferred_package_manager_for_ecosystem(Ecosystem::JavaScript)
+            .unwrap_or(PackageManager::Npm)
+    });
+    let package_manager_pin = package_manager_pin(&package_manager)?;
+    if package_manager_pin.is_none() {
+        eprintln!(
+            "⚠️ Could not determine the version of `{}` for `packageManager`.",
+            package_manager
+        );
+    }
+    let template =
+        package_json_template(&name, repository.as_deref(), package_manager_pin.as_deref());
+    if !exists(PACKAGE_JSON_PATH)? {
+        write(PACKAGE_JSON_PATH, template)?;
+        println!("Created: {}", PACKAGE_JSON_PATH);
+        return Ok(());
+    }
+    let source = read_to_string(PACKAGE_JSON_PATH)?;
+    let contents = merge_jsonc(
+        &source,
+        &template,
+        MergePolicies {
+            array: ArrayMergePolicy::Keep,
+            conflict: ConflictMergePolicy::Keep,
+        },
+    )?;
+    if contents == source {
+        println!("Already up to date: {}", PACKAGE_JSON_PATH);
+        return Ok(());
+    }
+    write(PACKAGE_JSON_PATH, contents)?;
+    println!("Updated: {}", PACKAGE_JSON_PATH);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        package_json::package_json_template,
+        structured_merge::{merge_jsonc, ArrayMergePolicy, ConflictMergePolicy, MergePolicies},
+    };
+
+    #[test]
+    fn test_package_json_template() {
+        let template = package_json_template("example", None, Some("bun@1.2.3"));
+        let value: serde_json::Value = serde_json::from_str(&template).unwrap();
+        assert_eq!(value["exports"]["."]["default"], "./dist/index.js");
+        assert_eq!(value["packageManager"], "bun@1.2.3");
+        assert!(value.get("repository").is_none());
+        assert!(template.find("\"name\"").unwrap() < template.find("\"exports\"").unwrap());
+
+        let merged = merge_jsonc(
+            "{\n  \"name\": \"existing\",\n  \"files\": [\"./src/\"]\n}\n",
+            &template,
+            MergePolicies {
+                array: ArrayMergePolicy::Keep,
+                conflict: ConflictMergePolicy::Keep,
+            },
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&merged).unwrap();
+        assert_eq!(value["name"], "existing");
+        assert_eq!(value["files"], serde_json::json!(["./src/"]));
+        assert_eq!(value["type"], "module");
+    }
+}
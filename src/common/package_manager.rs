@@ -4,7 +4,7 @@ use clap::{Args, ValueEnum};
 
 use self::ecosystem::Ecosystem;
 
-use super::ecosystem;
+use super::{ecosystem, error::RepoError};
 
 #[derive(Args, Debug)]
 pub(crate) struct PackageManagerArgs {
@@ -18,7 +18,9 @@ pub(crate) enum PackageManager {
     Bun,
     Yarn,
     Pnpm,
+    Deno,
     Cargo,
+    Bundler,
 }
 
 impl Display for PackageManager {
@@ -31,7 +33,9 @@ impl Display for PackageManager {
                 Self::Bun => "bun",
                 Self::Yarn => "yarn",
                 Self::Pnpm => "pnpm",
+                Self::Deno => "deno",
                 Self::Cargo => "cargo",
+                Self::Bundler => "bundler",
             }
         )
     }
@@ -43,33 +47,60 @@ pub(crate) const YARN_LOCK_PATH: &str = "./yarn.lock";
 pub(crate) const YARN_PNPM_LOCK_YAML_PATH: &str = "./pnpm-lock.yaml";
 const BUN_LOCK_PATH: &str = "./bun.lock";
 const BUN_LOCKB_PATH: &str = "./bun.lockb";
+pub(crate) const DENO_JSON_PATH: &str = "./deno.json";
+pub(crate) const DENO_JSONC_PATH: &str = "./deno.jsonc";
+pub(crate) const JSR_JSON_PATH: &str = "./jsr.json";
+pub(crate) const DENO_LOCK_PATH: &str = "./deno.lock";
 const CARGO_TOML: &str = "./Cargo.toml";
+pub(crate) const GEMFILE_PATH: &str = "./Gemfile";
 
 impl PackageManager {
+    /// For package managers that can be passed (or detected) for a command that doesn't support them.
+    pub(crate) fn unsupported_error(&self, purpose: &str) -> RepoError {
+        RepoError::user_error(format!("`{}` is not supported for {}.", self, purpose))
+    }
+
     pub(crate) fn auto_detect_preferred_package_manager_for_ecosystem(
         ecosystem: Ecosystem,
     ) -> Option<Self> {
         match ecosystem {
             Ecosystem::JavaScript => {
-                if exists(BUN_LOCK_PATH).unwrap() || exists(BUN_LOCKB_PATH).unwrap() {
+                if exists(BUN_LOCK_PATH).unwrap_or(false) || exists(BUN_LOCKB_PATH).unwrap_or(false)
+                {
                     Some(Self::Bun)
-                } else if exists(YARN_LOCK_PATH).unwrap() {
+                } else if exists(YARN_LOCK_PATH).unwrap_or(false) {
                     Some(Self::Yarn)
-                } else if exists(YARN_PNPM_LOCK_YAML_PATH).unwrap() {
+                } else if exists(YARN_PNPM_LOCK_YAML_PATH).unwrap_or(false) {
                     Some(Self::Pnpm)
-                } else if exists(PACKAGE_JSON_PATH).unwrap() {
+                } else if exists(PACKAGE_JSON_PATH).unwrap_or(false) {
                     Some(Self::Npm)
                 } else {
                     None
                 }
             }
+            Ecosystem::Jsr => {
+                if exists(DENO_JSON_PATH).unwrap_or(false)
+                    || exists(DENO_JSONC_PATH).unwrap_or(false)
+                {
+                    Some(Self::Deno)
+                } else {
+                    None
+                }
+            }
             Ecosystem::Rust => {
-                if exists(CARGO_TOML).unwrap() {
+                if exists(CARGO_TOML).unwrap_or(false) {
                     Some(Self::Cargo)
                 } else {
                     None
                 }
             }
+            Ecosystem::Ruby => {
+                if exists(GEMFILE_PATH).unwrap_or(false) {
+                    Some(Self::Bundler)
+                } else {
+                    None
+                }
+            }
         }
     }
 
@@ -79,7 +110,9 @@ impl PackageManager {
     //         PackageManager::Bun => Ecosystem::JavaScript,
     //         PackageManager::Yarn => Ecosystem::JavaScript,
     //         PackageManager::Pnpm => Ecosystem::JavaScript,
+    //         PackageManager::Deno => Ecosystem::Jsr,
     //         PackageManager::Cargo => Ecosystem::Rust,
+    //         PackageManager::Bundler => Ecosystem::Ruby,
     //     }
     // }
 }
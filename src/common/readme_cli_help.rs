@@ -0,0 +1,426 @@
+use std::{
+    collections::BTreeMap,
+    fs::{create_dir_all, exists, read_to_string, write},
+    ops::Range,
+    path::Path,
+};
+
+use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::{
+    command::command_must_get_stdout,
+    error::{RepoError, RepoResult},
+    jsonc::strip_jsonc,
+};
+
+pub(crate) const README_CLI_HELP_CONFIG_PATH: &str = "./.config/readme-cli-help.json";
+const README_PATH: &str = "./README.md";
+const DEFAULT_SCHEMA: &str = "../node_modules/readme-cli-help/schema.json";
+const HELP_INFO_STRING: &str = "text help";
+const FENCE: &str = "````";
+
+/// The config format of `readme-cli-help`.
+/// Unknown fields are preserved when the config is rewritten.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct ReadmeCliHelpConfig {
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    schema: Option<String>,
+    #[serde(default)]
+    pub(crate) files: BTreeMap<String, ReadmeCliHelpFile>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReadmeCliHelpFile {
+    #[serde(default)]
+    pub(crate) code_fences: Vec<CodeFence>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CodeFence {
+    pub(crate) info_string: String,
+    pub(crate) command: Vec<String>,
+    #[serde(flatten)]
+    extra: Map<String, Value>,
+}
+
+impl ReadmeCliHelpConfig {
+    pub(crate) fn read() -> RepoResult<Option<Self>> {
+        if !exists(README_CLI_HELP_CONFIG_PATH)? {
+            return Ok(None);
+        }
+        let source = read_to_string(README_CLI_HELP_CONFIG_PATH)?;
+        serde_json::from_str(&strip_jsonc(&source))
+            .map(Some)
+            .map_err(|e| {
+                RepoError::config(format!(
+                    "Could not parse `{}`: {}",
+                    README_CLI_HELP_CONFIG_PATH, e
+                ))
+            })
+    }
+
+    /// Pretty-printed, except that commands are kept on a single line (matching the upstream examples).
+    fn to_json(&self) -> RepoResult<String> {
+        let Ok(json) = serde_json::to_string_pretty(self) else {
+            return Err(RepoError::internal(
+                "Could not serialize `readme-cli-help` config.",
+            ));
+        };
+        let command_array = static_regex!(r#""command": \[(?:\s*"(?:[^"\\]|\\.)*",?)*\s*\]"#);
+        let string_literal = static_regex!(r#""(?:[^"\\]|\\.)*""#);
+        let mut contents = String::new();
+        let mut offset = 0;
+        for array in command_array.find_iter(&json) {
+            let args: Vec<&str> = string_literal
+                .find_iter(&array.as_str()["\"command\"".len()..])
+                .map(|arg| arg.as_str())
+                .collect();
+            contents.push_str(&json[offset..array.start()]);
+            contents.push_str(&format!("\"command\": [{}]", args.join(", ")));
+            offset = array.end();
+        }
+        contents.push_str(&json[offset..]);
+        Ok(format!("{}\n", contents))
+    }
+
+    pub(crate) fn write(&self) -> RepoResult<()> {
+        if let Some(parent) = Path::new(README_CLI_HELP_CONFIG_PATH).parent() {
+            create_dir_all(parent)?;
+        }
+        write(README_CLI_HELP_CONFIG_PATH, self.to_json()?)?;
+        Ok(())
+    }
+}
+
+/// Runs a help command, returning its output (trimmed).
+pub(crate) fn run_help_command(command: &[String]) -> RepoResult<String> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(RepoError::config("Empty `readme-cli-help` command."));
+    };
+    let mut command = PrintableShellCommand::new(program);
+    command.args(args);
+    command_must_get_stdout(command)
+}
+
+/// Subcommand names from the `Commands:` section of clap-style help output (excluding `help`).
+fn help_subcommands(help: &str) -> Vec<String> {
+    let mut lines = help
+        .lines()
+        .skip_while(|line| line.trim_end() != "Commands:")
+        .skip(1);
+    let mut subcommands = vec![];
+    for line in lines.by_ref() {
+        if !line.starts_with(' ') {
+            break;
+        }
+        // Continuation lines of wrapped descriptions are indented further.
+        if line.starts_with("      ") {
+            continue;
+        }
+        let Some(name) = line.split_whitespace().next() else {
+            continue;
+        };
+        let name = name.trim_end_matches(',');
+        if name != "help" {
+            subcommands.push(name.to_owned());
+        }
+    }
+    subcommands
+}
+
+/// A command whose help output is embedded in the README.
+pub(crate) struct DiscoveredHelp {
+    /// Subcommand path, e.g. `["boilerplate", "ci"]` (empty for the root command).
+    pub(crate) subcommands: Vec<String>,
+    pub(crate) output: String,
+}
+
+impl DiscoveredHelp {
+    fn info_string(&self) -> String {
+        if self.subcommands.is_empty() {
+            return HELP_INFO_STRING.to_owned();
+        }
+        format!("{}-{}", HELP_INFO_STRING, self.subcommands.join("-"))
+    }
+}
+
+/// Runs `<command> --help` and recursively `<command> <subcommand…> --help`, up to `max_depth` levels of subcommands.
+/// Results are in pre-order, matching the order of the `Commands:` sections.
+pub(crate) fn discover_help(
+    command: &[String],
+    max_depth: usize,
+) -> RepoResult<Vec<DiscoveredHelp>> {
+    let mut discovered = vec![];
+    let mut pending = vec![vec![]];
+    while let Some(subcommands) = pending.pop() {
+        let output = run_help_command(&help_command(command, &subcommands))?;
+        if subcommands.len() < max_depth {
+            for subcommand in help_subcommands(&output).into_iter().rev() {
+                let mut child = subcommands.clone();
+                child.push(subcommand);
+                pending.push(child);
+            }
+        }
+        discovered.push(DiscoveredHelp {
+            subcommands,
+            output,
+        });
+    }
+    Ok(discovered)
+}
+
+fn help_command(command: &[String], subcommands: &[String]) -> Vec<String> {
+    command
+        .iter()
+        .chain(subcommands)
+        .cloned()
+        .chain(["--help".to_owned()])
+        .collect()
+}
+
+/// The binary name from `Usage: <name> …`, falling back to the file name of the program.
+fn binary_name(command: &[String], root_help: &str) -> String {
+    if let Some(captures) = static_regex!(r"(?m)^Usage: (\S+)").captures(root_help) {
+        return captures[1].to_owned();
+    }
+    command
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// The location of a fenced code block with the given info string.
+struct FencedBlock {
+    /// The lines between the opening and closing fence.
+    contents: Range<usize>,
+    /// The end of the closing fence line (including its newline).
+    end: usize,
+}
+
+fn find_fenced_block(source: &str, info_string: &str) -> Option<FencedBlock> {
+    let mut offset = 0;
+    let mut opening: Option<(&str, usize)> = None;
+    for line in source.split_inclusive('\n') {
+        let line_end = offset + line.len();
+        let trimmed = line.trim_end();
+        match opening {
+            None => {
+                let backticks = trimmed.trim_start_matches('`');
+                let fence = &trimmed[..trimmed.len() - backticks.len()];
+                if fence.len() >= 3 && backticks.trim() == info_string {
+                    opening = Some((fence, line_end));
+                }
+            }
+            Some((fence, contents_start)) => {
+                if trimmed == fence {
+                    return Some(FencedBlock {
+                        contents: contents_start..offset,
+                        end: line_end,
+                    });
+                }
+            }
+        }
+        offset = line_end;
+    }
+    None
+}
+
+fn readme_section(binary_name: &str, help: &DiscoveredHelp) -> String {
+    let title = [binary_name.to_owned()]
+        .into_iter()
+        .chain(help.subcommands.iter().cloned())
+        .collect::<Vec<String>>()
+        .join(" ");
+    format!(
+        "## `{}`\n\n{}{}\n{}\n{}\n",
+        title,
+        FENCE,
+        help.info_string(),
+        help.output,
+        FENCE
+    )
+}
+
+/// Adds sections for discovered commands that are missing from the README, each after the section of the preceding command (or at the end).
+fn add_readme_sections(source: &str, binary_name: &str, discovered: &[DiscoveredHelp]) -> String {
+    let mut source = source.to_owned();
+    let mut insert_at = None;
+    for help in discovered {
+        if let Some(block) = find_fenced_block(&source, &help.info_string()) {
+            insert_at = Some(block.end);
+            continue;
+        }
+        let position = insert_at.unwrap_or(source.len());
+        let before = &source[..position];
+        let separator = if before.is_empty() || before.ends_with("\n\n") {
+            ""
+        } else if before.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+        let section = format!("{}{}", separator, readme_section(binary_name, help));
+        let after_separator = if source[position..].starts_with('\n') || position == source.len() {
+            ""
+        } else {
+            "\n"
+        };
+        source.insert_str(position, &format!("{}{}", section, after_separator));
+        insert_at = Some(position + section.len());
+    }
+    source
+}
+
+/// Replaces the contents of the fenced block with the given info string, or returns `None` if there is no such block.
+fn replace_fenced_block(source: &str, info_string: &str, output: &str) -> Option<String> {
+    let block = find_fenced_block(source, info_string)?;
+    Some(format!(
+        "{}{}\n{}",
+        &source[..block.contents.start],
+        output,
+        &source[block.contents.end..]
+    ))
+}
+
+/// Runs the command for each code fence in the config and replaces the fence contents with its output.
+/// If `check` is set, files are left untouched and any differences are reported instead.
+pub(crate) fn sync_readme_cli_help(check: bool) -> RepoResult<()> {
+    let Some(config) = ReadmeCliHelpConfig::read()? else {
+        return Err(RepoError::detection_failure(format!(
+            "No config found at: {}",
+            README_CLI_HELP_CONFIG_PATH
+        )));
+    };
+    let mut num_out_of_date = 0;
+    for (path, file) in &config.files {
+        let source = read_to_string(path)?;
+        let mut contents = source.clone();
+        for code_fence in &file.code_fences {
+            let output = run_help_command(&code_fence.command)?;
+            let Some(updated) = replace_fenced_block(&contents, &code_fence.info_string, &output)
+            else {
+                return Err(RepoError::user_error(format!(
+                    "Could not find a code fence with info string `{}` in: {}",
+                    code_fence.info_string, path
+                )));
+            };
+            if updated != contents {
+                if check {
+                    println!("❌ {} `{}` is out of date.", path, code_fence.info_string);
+                }
+                num_out_of_date += 1;
+            }
+            contents = updated;
+        }
+        if !check && contents != source {
+            write(path, contents)?;
+            println!("Updated: {}", path);
+        }
+    }
+    if check && num_out_of_date > 0 {
+        return Err(RepoError::check_failed(format!(
+            "Found {} out-of-date code fence(s). Run `repo readme-cli-help update` to update them.",
+            num_out_of_date
+        )));
+    }
+    if num_out_of_date == 0 {
+        println!("✅ All code fences are up to date.");
+    }
+    Ok(())
+}
+
+/// Adds a code fence entry (and a README section) for every discovered command that doesn't have one yet.
+pub(crate) fn add_discovered_help(command: &[String], max_depth: usize) -> RepoResult<()> {
+    let discovered = discover_help(command, max_depth)?;
+    let Some(root) = discovered.first() else {
+        return Err(RepoError::internal("No help output was discovered."));
+    };
+    let binary_name = binary_name(command, &root.output);
+
+    let mut config = ReadmeCliHelpConfig::read()?.unwrap_or_else(|| ReadmeCliHelpConfig {
+        schema: Some(DEFAULT_SCHEMA.to_owned()),
+        ..ReadmeCliHelpConfig::default()
+    });
+    let file = config.files.entry(README_PATH.to_owned()).or_default();
+    let mut num_added = 0;
+    for help in &discovered {
+        let info_string = help.info_string();
+        if file
+            .code_fences
+            .iter()
+            .any(|code_fence| code_fence.info_string == info_string)
+        {
+            continue;
+        }
+        file.code_fences.push(CodeFence {
+            info_string,
+            command: help_command(command, &help.subcommands),
+            extra: Map::default(),
+        });
+        num_added += 1;
+    }
+    config.write()?;
+    println!(
+        "{}: {} code fence(s) added ({} commands discovered)",
+        README_CLI_HELP_CONFIG_PATH,
+        num_added,
+        discovered.len()
+    );
+
+    let readme = read_to_string(README_PATH).unwrap_or_default();
+    let updated = add_readme_sections(&readme, &binary_name, &discovered);
+    if updated != readme {
+        write(README_PATH, updated)?;
+        println!("Updated: {}", README_PATH);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::readme_cli_help::{
+        add_readme_sections, find_fenced_block, help_subcommands, replace_fenced_block,
+        DiscoveredHelp,
+    };
+
+    #[test]
+    fn test_readme_cli_help() {
+        let help = "Set up boilerplate\n\nUsage: repo boilerplate <COMMAND>\n\nCommands:\n  ci    Set up CI\n  list  List templates, including overrides\n        and extra templates\n  help  Print this message\n\nOptions:\n  -h, --help  Print help";
+        assert_eq!(help_subcommands(help), vec!["ci", "list"]);
+
+        let readme = "# repo\n\n## `repo`\n\n````text help\nold\n````\n\n## License\n";
+        let block = find_fenced_block(readme, "text help").unwrap();
+        assert_eq!(&readme[block.contents], "old\n");
+        assert_eq!(
+            replace_fenced_block(readme, "text help", "new\nlines"),
+            Some(readme.replace("old\n", "new\nlines\n"))
+        );
+        assert_eq!(replace_fenced_block(readme, "text help-ci", "new"), None);
+        let discovered = [
+            DiscoveredHelp {
+                subcommands: vec![],
+                output: "root".to_owned(),
+            },
+            DiscoveredHelp {
+                subcommands: vec!["ci".to_owned()],
+                output: "ci help".to_owned(),
+            },
+        ];
+        let updated = add_readme_sections(readme, "repo", &discovered);
+        assert_eq!(
+            updated,
+            "# repo\n\n## `repo`\n\n````text help\nold\n````\n\n## `repo ci`\n\n````text help-ci\nci help\n````\n\n## License\n"
+        );
+        assert_eq!(add_readme_sections(&updated, "repo", &discovered), updated);
+    }
+}
@@ -0,0 +1,41 @@
+use clap::Args;
+
+use crate::common::{
+    config::Config,
+    ecosystem::Ecosystem,
+    error::{RepoError, RepoResult},
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct RegistryArgs {
+    /// Registry URL (`npm`, `gem`) or registry name from `[registries]` (`cargo`). Defaults to `registries` in `.config/repo.json`.
+    #[clap(long)]
+    registry: Option<String>,
+}
+
+impl RegistryArgs {
+    pub(crate) fn registry_for_ecosystem(
+        &self,
+        ecosystem: Ecosystem,
+    ) -> RepoResult<Option<String>> {
+        if ecosystem == Ecosystem::Jsr {
+            if self.registry.is_some() {
+                return Err(RepoError::user_error(format!(
+                    "Alternate registries are not supported for ecosystem: {}",
+                    ecosystem
+                )));
+            }
+            return Ok(None);
+        }
+        if let Some(registry) = &self.registry {
+            return Ok(Some(registry.clone()));
+        }
+        let registries = &Config::get()?.registries;
+        Ok(match ecosystem {
+            Ecosystem::JavaScript => registries.npm.clone(),
+            Ecosystem::Rust => registries.cargo.clone(),
+            Ecosystem::Ruby => registries.rubygems.clone(),
+            Ecosystem::Jsr => None,
+        })
+    }
+}
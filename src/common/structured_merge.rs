@@ -0,0 +1,497 @@
+use std::ops::Range;
+
+use clap::ValueEnum;
+use toml_edit::{DocumentMut, Item, TableLike, Value};
+
+use crate::common::{
+    error::{RepoError, RepoResult},
+    jsonc::{parse_jsonc, strip_jsonc, JsoncMember, JsoncNode},
+};
+
+/// How to merge an array that is present in both the existing file and the template.
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+pub(crate) enum ArrayMergePolicy {
+    /// Append template entries that are not already present.
+    #[default]
+    Union,
+    /// Use the template array.
+    Replace,
+    /// Use the existing array.
+    Keep,
+}
+
+/// How to merge a (non-object, non-array) value that differs between the existing file and the template.
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+pub(crate) enum ConflictMergePolicy {
+    /// Use the existing value.
+    #[default]
+    Keep,
+    /// Use the template value.
+    Template,
+    /// Fail without writing anything.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MergePolicies {
+    pub(crate) array: ArrayMergePolicy,
+    pub(crate) conflict: ConflictMergePolicy,
+}
+
+fn conflicts_error(conflicts: Vec<String>) -> RepoResult<()> {
+    if conflicts.is_empty() {
+        return Ok(());
+    }
+    Err(RepoError::user_error(format!(
+        "The existing file has values that differ from the template (pass `--conflict-policy keep` or `--conflict-policy template` to merge anyway):\n{}",
+        conflicts
+            .iter()
+            .map(|path| format!("- {}", path.trim_start_matches('.')))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )))
+}
+
+/// Returns the indentation of the line containing `position`.
+fn line_indent(source: &str, position: usize) -> &str {
+    let line_start = source[..position].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Re-indents all lines after the first from `from_indent` to `to_indent`.
+fn reindent(text: &str, from_indent: &str, to_indent: &str) -> String {
+    text.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.to_owned()
+            } else {
+                format!(
+                    "{}{}",
+                    to_indent,
+                    line.strip_prefix(from_indent).unwrap_or(line)
+                )
+            }
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+struct JsoncMerge<'a> {
+    existing: &'a str,
+    existing_stripped: String,
+    template: &'a str,
+    template_stripped: String,
+    policies: MergePolicies,
+    edits: Vec<(Range<usize>, String)>,
+    conflicts: Vec<String>,
+}
+
+impl JsoncMerge<'_> {
+    fn existing_value(&self, node: &JsoncNode) -> Option<serde_json::Value> {
+        serde_json::from_str(&self.existing_stripped[node.range()]).ok()
+    }
+
+    fn template_value(&self, node: &JsoncNode) -> Option<serde_json::Value> {
+        serde_json::from_str(&self.template_stripped[node.range()]).ok()
+    }
+
+    /// The template text for a node, re-indented to match the given position in the existing file.
+    fn template_text(&self, node: &JsoncNode, existing_indent: &str) -> String {
+        let range = node.range();
+        reindent(
+            &self.template[range.clone()],
+            line_indent(self.template, range.start),
+            existing_indent,
+        )
+    }
+
+    fn replace(&mut self, existing_node: &JsoncNode, template_node: &JsoncNode) {
+        let range = existing_node.range();
+        let text = self.template_text(template_node, line_indent(self.existing, range.start));
+        self.edits.push((range, text));
+    }
+
+    /// Inserts entries after the last existing entry, or into an empty object/array.
+    fn append(
+        &mut self,
+        container_range: Range<usize>,
+        last_range: Option<Range<usize>>,
+        entries: Vec<String>,
+    ) {
+        if entries.is_empty() {
+            return;
+        }
+        match last_range {
+            Some(last_range) => {
+                let multiline =
+                    self.existing[container_range.start..last_range.start].contains('\n');
+                let separator = if multiline {
+                    format!(",\n{}", line_indent(self.existing, last_range.start))
+                } else {
+                    ", ".to_owned()
+                };
+                let text: String = entries
+                    .iter()
+                    .map(|entry| format!("{}{}", separator, entry))
+                    .collect();
+                self.edits.push((last_range.end..last_range.end, text));
+            }
+            None => {
+                let position = container_range.start + 1;
+                self.edits.push((position..position, entries.join(", ")));
+            }
+        }
+    }
+
+    fn merge_objects(
+        &mut self,
+        path: &str,
+        existing_range: Range<usize>,
+        existing_members: &[JsoncMember],
+        template_members: &[JsoncMember],
+    ) {
+        let member_indent = match existing_members.last() {
+            Some(last) => line_indent(self.existing, last.key_range.start).to_owned(),
+            None => format!("{}  ", line_indent(self.existing, existing_range.start)),
+        };
+        let mut new_members = vec![];
+        for template_member in template_members {
+            let member_path = format!("{}.{}", path, template_member.key);
+            match existing_members
+                .iter()
+                .find(|existing_member| existing_member.key == template_member.key)
+            {
+                Some(existing_member) => {
+                    self.merge_nodes(&member_path, &existing_member.value, &template_member.value)
+                }
+                None => new_members.push(format!(
+                    "{}: {}",
+                    &self.template[template_member.key_range.clone()],
+                    self.template_text(&template_member.value, &member_indent)
+                )),
+            }
+        }
+        if existing_members.is_empty() && !new_members.is_empty() {
+            let outer_indent = line_indent(self.existing, existing_range.start).to_owned();
+            let position = existing_range.start + 1;
+            self.edits.push((
+                position..position,
+                format!(
+                    "\n{}{}\n{}",
+                    member_indent,
+                    new_members.join(&format!(",\n{}", member_indent)),
+                    outer_indent
+                ),
+            ));
+            return;
+        }
+        self.append(
+            existing_range,
+            existing_members
+                .last()
+                .map(|last| last.key_range.start..last.value.range().end),
+            new_members,
+        );
+    }
+
+    fn merge_nodes(&mut self, path: &str, existing_node: &JsoncNode, template_node: &JsoncNode) {
+        match (existing_node, template_node) {
+            (
+                JsoncNode::Object {
+                    range,
+                    members: existing_members,
+                },
+                JsoncNode::Object {
+                    members: template_members,
+                    ..
+                },
+            ) => self.merge_objects(path, range.clone(), existing_members, template_members),
+            (
+                JsoncNode::Array {
+                    range,
+                    elements: existing_elements,
+                },
+                JsoncNode::Array {
+                    elements: template_elements,
+                    ..
+                },
+            ) => match self.policies.array {
+                ArrayMergePolicy::Union => {
+                    let existing_values: Vec<Option<serde_json::Value>> = existing_elements
+                        .iter()
+                        .map(|element| self.existing_value(element))
+                        .collect();
+                    let element_indent = existing_elements
+                        .last()
+                        .map(|last| line_indent(self.existing, last.range().start).to_owned())
+                        .unwrap_or_default();
+                    let new_elements: Vec<String> = template_elements
+                        .iter()
+                        .filter(|element| !existing_values.contains(&self.template_value(element)))
+                        .map(|element| self.template_text(element, &element_indent))
+                        .collect();
+                    self.append(
+                        range.clone(),
+                        existing_elements.last().map(JsoncNode::range),
+                        new_elements,
+                    );
+                }
+                ArrayMergePolicy::Replace => {
+                    if self.existing_value(existing_node) != self.template_value(template_node) {
+                        self.replace(existing_node, template_node);
+                    }
+                }
+                ArrayMergePolicy::Keep => {}
+            },
+            _ => {
+                if self.existing_value(existing_node) == self.template_value(template_node) {
+                    return;
+                }
+                match self.policies.conflict {
+                    ConflictMergePolicy::Keep => {}
+                    ConflictMergePolicy::Template => self.replace(existing_node, template_node),
+                    ConflictMergePolicy::Error => self.conflicts.push(path.to_owned()),
+                }
+            }
+        }
+    }
+}
+
+/// Merges the keys of a JSON/JSONC template into an existing file, preserving its comments and formatting.
+pub(crate) fn merge_jsonc(
+    existing: &str,
+    template: &str,
+    policies: MergePolicies,
+) -> RepoResult<String> {
+    let Some(existing_node @ JsoncNode::Object { .. }) = parse_jsonc(existing) else {
+        return Err(RepoError::user_error(
+            "The existing file is not a JSON/JSONC object.",
+        ));
+    };
+    let Some(template_node @ JsoncNode::Object { .. }) = parse_jsonc(template) else {
+        return Err(RepoError::internal(
+            "The template is not a JSON/JSONC object.",
+        ));
+    };
+    let mut merge = JsoncMerge {
+        existing,
+        existing_stripped: strip_jsonc(existing),
+        template,
+        template_stripped: strip_jsonc(template),
+        policies,
+        edits: vec![],
+        conflicts: vec![],
+    };
+    merge.merge_nodes("", &existing_node, &template_node);
+    conflicts_error(merge.conflicts)?;
+
+    let mut edits = merge.edits;
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut merged = existing.to_owned();
+    for (range, text) in edits.into_iter().rev() {
+        merged.replace_range(range, &text);
+    }
+    if parse_jsonc(&merged).is_none() {
+        return Err(RepoError::internal("Merging produced invalid JSON/JSONC."));
+    }
+    Ok(merged)
+}
+
+fn toml_value_key(value: &Value) -> String {
+    let mut value = value.clone();
+    value.decor_mut().clear();
+    value.to_string()
+}
+
+fn merge_toml_tables(
+    path: &str,
+    existing: &mut dyn TableLike,
+    template: &dyn TableLike,
+    policies: MergePolicies,
+    conflicts: &mut Vec<String>,
+) {
+    for (key, template_item) in template.iter() {
+        let item_path = format!("{}.{}", path, key);
+        match existing.get_mut(key) {
+            Some(existing_item) => merge_toml_items(
+                &item_path,
+                existing_item,
+                template_item,
+                policies,
+                conflicts,
+            ),
+            None => {
+                existing.insert(key, template_item.clone());
+            }
+        }
+    }
+}
+
+fn merge_toml_items(
+    path: &str,
+    existing: &mut Item,
+    template: &Item,
+    policies: MergePolicies,
+    conflicts: &mut Vec<String>,
+) {
+    if let (Some(existing_table), Some(template_table)) =
+        (existing.as_table_like_mut(), template.as_table_like())
+    {
+        merge_toml_tables(path, existing_table, template_table, policies, conflicts);
+        return;
+    }
+    if let (Some(existing_array), Some(template_array)) =
+        (existing.as_array_mut(), template.as_array())
+    {
+        match policies.array {
+            ArrayMergePolicy::Union => {
+                let existing_keys: Vec<String> =
+                    existing_array.iter().map(toml_value_key).collect();
+                for value in template_array.iter() {
+                    if !existing_keys.contains(&toml_value_key(value)) {
+                        existing_array.push(value.clone());
+                    }
+                }
+            }
+            ArrayMergePolicy::Replace => *existing = template.clone(),
+            ArrayMergePolicy::Keep => {}
+        }
+        return;
+    }
+    if let (Some(existing_array), Some(template_array)) = (
+        existing.as_array_of_tables_mut(),
+        template.as_array_of_tables(),
+    ) {
+        match policies.array {
+            ArrayMergePolicy::Union => {
+                let existing_keys: Vec<String> = existing_array
+                    .iter()
+                    .map(|table| table.to_string())
+                    .collect();
+                for table in template_array.iter() {
+                    if !existing_keys.contains(&table.to_string()) {
+                        existing_array.push(table.clone());
+                    }
+                }
+            }
+            ArrayMergePolicy::Replace => *existing = template.clone(),
+            ArrayMergePolicy::Keep => {}
+        }
+        return;
+    }
+    let matches = match (existing.as_value(), template.as_value()) {
+        (Some(existing_value), Some(template_value)) => {
+            toml_value_key(existing_value) == toml_value_key(template_value)
+        }
+        _ => false,
+    };
+    if matches {
+        return;
+    }
+    match policies.conflict {
+        ConflictMergePolicy::Keep => {}
+        ConflictMergePolicy::Template => {
+            let decor = existing.as_value().map(|value| value.decor().clone());
+            *existing = template.clone();
+            if let (Some(decor), Some(value)) = (decor, existing.as_value_mut()) {
+                *value.decor_mut() = decor;
+            }
+        }
+        ConflictMergePolicy::Error => conflicts.push(path.to_owned()),
+    }
+}
+
+/// Merges the keys of a TOML template into an existing file, preserving its comments and formatting.
+pub(crate) fn merge_toml(
+    existing: &str,
+    template: &str,
+    policies: MergePolicies,
+) -> RepoResult<String> {
+    let mut existing_document: DocumentMut = existing.parse().map_err(|e| {
+        RepoError::user_error(format!("The existing file is not valid TOML: {}", e))
+    })?;
+    let template_document: DocumentMut = template
+        .parse()
+        .map_err(|e| RepoError::internal(format!("The template is not valid TOML: {}", e)))?;
+    let mut conflicts = vec![];
+    merge_toml_tables(
+        "",
+        existing_document.as_table_mut(),
+        template_document.as_table(),
+        policies,
+        &mut conflicts,
+    );
+    conflicts_error(conflicts)?;
+    Ok(existing_document.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::structured_merge::{
+        merge_jsonc, merge_toml, ArrayMergePolicy, ConflictMergePolicy, MergePolicies,
+    };
+
+    #[test]
+    fn test_merge_jsonc() {
+        let existing = r#"{
+  // Our own settings.
+  "files": {
+    "includes": ["**", "!!dist"],
+  },
+  "formatter": { "indentStyle": "tab" }
+}
+"#;
+        let template = r#"{
+  "$schema": "./schema.json",
+  "files": {
+    "includes": [
+      "**",
+      "!package.json"
+    ]
+  },
+  "formatter": { "indentStyle": "space", "lineWidth": 80 }
+}
+"#;
+        assert_eq!(
+            merge_jsonc(existing, template, MergePolicies::default()).unwrap(),
+            r#"{
+  // Our own settings.
+  "files": {
+    "includes": ["**", "!!dist", "!package.json"],
+  },
+  "formatter": { "indentStyle": "tab", "lineWidth": 80 },
+  "$schema": "./schema.json"
+}
+"#
+        );
+        assert!(merge_jsonc(
+            existing,
+            template,
+            MergePolicies {
+                array: ArrayMergePolicy::Keep,
+                conflict: ConflictMergePolicy::Error
+            }
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_merge_toml() {
+        let existing = "# Ours\n[install]\nexact = false # keep\n";
+        let template = "[install]\nexact = true\n\n[test]\npreload = [\"./setup.ts\"]\n";
+        assert_eq!(
+            merge_toml(
+                existing,
+                template,
+                MergePolicies {
+                    array: ArrayMergePolicy::Union,
+                    conflict: ConflictMergePolicy::Template
+                }
+            )
+            .unwrap(),
+            "# Ours\n[install]\nexact = true # keep\n\n[test]\npreload = [\"./setup.ts\"]\n"
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use std::{
+    collections::BTreeMap,
+    env::current_dir,
+    fs::{exists, read_to_string},
+    sync::OnceLock,
+};
+
+use clap::Args;
+use printable_shell_command::PrintableShellCommand;
+use regex_static::static_regex;
+use serde::{Deserialize, Serialize};
+
+use crate::common::{
+    ecosystem::Ecosystem,
+    error::{RepoError, RepoResult},
+    forge::VCSForge,
+    inference::get_stdout,
+    package_manager::{PackageManager, PACKAGE_JSON_PATH},
+};
+
+pub(crate) const CARGO_TOML_PATH: &str = "./Cargo.toml";
+pub(crate) const RUST_TOOLCHAIN_TOML_PATH: &str = "./rust-toolchain.toml";
+const NODE_VERSION_PATHS: [&str; 2] = ["./.node-version", "./.nvmrc"];
+const DEFAULT_NODE_VERSION: &str = "24";
+const DEFAULT_RUST_TOOLCHAIN_CHANNEL: &str = "stable";
+
+// Overrides for variables that are otherwise detected from the project.
+// These are also recorded in `.config/repo-boilerplate.json` so that upgrades render templates the same way.
+#[derive(Args, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct TemplateVariableArgs {
+    /// Defaults to the name in `package.json` or `Cargo.toml`, else the folder name.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) project_name: Option<String>,
+
+    /// Can be specified multiple times. Defaults to all detected ecosystems.
+    #[clap(long = "ecosystem")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) ecosystems: Vec<Ecosystem>,
+
+    /// Defaults to `.node-version` or `.nvmrc`, else the major version of `node`.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) node_version: Option<String>,
+
+    /// Defaults to the channel in `rust-toolchain.toml`, else the version of `rustc`.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) rust_toolchain_channel: Option<String>,
+
+    /// Defaults to the forge of the `origin` remote.
+    #[clap(long)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) forge: Option<VCSForge>,
+}
+
+impl TemplateVariableArgs {
+    pub(crate) fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Uses the values from `self`, falling back to those from `other`.
+    pub(crate) fn or(self, other: Self) -> Self {
+        Self {
+            project_name: self.project_name.or(other.project_name),
+            ecosystems: if self.ecosystems.is_empty() {
+                other.ecosystems
+            } else {
+                self.ecosystems
+            },
+            node_version: self.node_version.or(other.node_version),
+            rust_toolchain_channel: self.rust_toolchain_channel.or(other.rust_toolchain_channel),
+            forge: self.forge.or(other.forge),
+        }
+    }
+}
+
+/// Values available to templates as `{{project_name}}`, `{{ecosystems}}`, `{{node_version}}`, `{{rust_toolchain_channel}}`, and `{{forge}}`.
+/// Conditional sections can use `{{#if ecosystem.rust}}…{{else}}…{{/if}}` or `{{#if forge.github}}…{{/if}}`.
+#[derive(Debug)]
+pub(crate) struct TemplateVariables {
+    pub(crate) project_name: String,
+    pub(crate) ecosystems: Vec<Ecosystem>,
+    pub(crate) node_version: String,
+    pub(crate) rust_toolchain_channel: String,
+    pub(crate) forge: VCSForge,
+}
+
+/// The `[package]` name from `Cargo.toml`, which is also the name of the default binary.
+pub(crate) fn detect_cargo_package_name() -> Option<String> {
+    let cargo_toml = read_to_string(CARGO_TOML_PATH).ok()?;
+    let package_name = static_regex!(r#"(?m)^\[package\][^\[]*?^name\s*=\s*"([^"]+)""#);
+    Some(package_name.captures(&cargo_toml)?[1].to_owned())
+}
+
+pub(crate) fn detect_project_name() -> Option<String> {
+    if let Ok(package_json) = read_to_string(PACKAGE_JSON_PATH) {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&package_json) {
+            if let Some(name) = value["name"].as_str() {
+                return Some(name.to_owned());
+            }
+        }
+    }
+    if let Some(name) = detect_cargo_package_name() {
+        return Some(name);
+    }
+    current_dir()
+        .ok()?
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+fn detect_ecosystems() -> Vec<Ecosystem> {
+    [
+        Ecosystem::JavaScript,
+        Ecosystem::Jsr,
+        Ecosystem::Rust,
+        Ecosystem::Ruby,
+    ]
+    .into_iter()
+    .filter(|ecosystem| {
+        PackageManager::auto_detect_preferred_package_manager_for_ecosystem(*ecosystem).is_some()
+    })
+    .collect()
+}
+
+fn detect_node_version() -> Option<String> {
+    for node_version_path in NODE_VERSION_PATHS {
+        if let Ok(node_version) = read_to_string(node_version_path) {
+            let node_version = node_version.trim();
+            if !node_version.is_empty() {
+                return Some(node_version.trim_start_matches('v').to_owned());
+            }
+        }
+    }
+    let mut node_command = PrintableShellCommand::new("node");
+    node_command.arg("--version");
+    let node_version = get_stdout(node_command)?;
+    node_version
+        .trim_start_matches('v')
+        .split('.')
+        .next()
+        .map(str::to_owned)
+}
+
+fn detect_rust_toolchain_channel() -> Option<String> {
+    if exists(RUST_TOOLCHAIN_TOML_PATH).unwrap_or(false) {
+        let rust_toolchain_toml = read_to_string(RUST_TOOLCHAIN_TOML_PATH).ok()?;
+        let channel = static_regex!(r#"(?m)^channel\s*=\s*"([^"]+)""#);
+        if let Some(captures) = channel.captures(&rust_toolchain_toml) {
+            return Some(captures[1].to_owned());
+        }
+    }
+    let mut rustc_command = PrintableShellCommand::new("rustc");
+    rustc_command.arg("--version");
+    // e.g. `rustc 1.90.0 (1159e78c4 2025-09-14)`
+    let rustc_version = get_stdout(rustc_command)?;
+    rustc_version.split_whitespace().nth(1).map(str::to_owned)
+}
+
+/// Detection reads project files and spawns `node`, `rustc`, and `git`/`jj`, so each value is only detected once per run (e.g. `repo boilerplate upgrade` renders every recorded file).
+fn detected<T: Clone>(cell: &OnceLock<T>, detect: impl FnOnce() -> T) -> T {
+    cell.get_or_init(detect).clone()
+}
+
+impl TemplateVariables {
+    pub(crate) fn from_args_or_detect(template_variable_args: &TemplateVariableArgs) -> Self {
+        static PROJECT_NAME: OnceLock<Option<String>> = OnceLock::new();
+        static ECOSYSTEMS: OnceLock<Vec<Ecosystem>> = OnceLock::new();
+        static NODE_VERSION: OnceLock<Option<String>> = OnceLock::new();
+        static RUST_TOOLCHAIN_CHANNEL: OnceLock<Option<String>> = OnceLock::new();
+        static FORGE: OnceLock<Option<VCSForge>> = OnceLock::new();
+
+        let template_variable_args = template_variable_args.clone();
+        Self {
+            project_name: template_variable_args
+                .project_name
+                .or_else(|| detected(&PROJECT_NAME, detect_project_name))
+                .unwrap_or_default(),
+            ecosystems: if template_variable_args.ecosystems.is_empty() {
+                detected(&ECOSYSTEMS, detect_ecosystems)
+            } else {
+                template_variable_args.ecosystems
+            },
+            node_version: template_variable_args
+                .node_version
+                .or_else(|| detected(&NODE_VERSION, detect_node_version))
+                .unwrap_or_else(|| DEFAULT_NODE_VERSION.to_owned()),
+            rust_toolchain_channel: template_variable_args
+                .rust_toolchain_channel
+                .or_else(|| detected(&RUST_TOOLCHAIN_CHANNEL, detect_rust_toolchain_channel))
+                .unwrap_or_else(|| DEFAULT_RUST_TOOLCHAIN_CHANNEL.to_owned()),
+            forge: template_variable_args
+                .forge
+                .or_else(|| detected(&FORGE, VCSForge::auto_detect))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn values(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("project_name".to_owned(), self.project_name.clone()),
+            (
+                "ecosystems".to_owned(),
+                self.ecosystems
+                    .iter()
+                    .map(|ecosystem| ecosystem.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            ("node_version".to_owned(), self.node_version.clone()),
+            (
+                "rust_toolchain_channel".to_owned(),
+                self.rust_toolchain_channel.clone(),
+            ),
+            ("forge".to_owned(), forge_name(self.forge).to_owned()),
+        ])
+    }
+
+    fn condition(&self, name: &str) -> Option<bool> {
+        if let Some(ecosystem_name) = name.strip_prefix("ecosystem.") {
+            let ecosystem = [
+                Ecosystem::JavaScript,
+                Ecosystem::Jsr,
+                Ecosystem::Rust,
+                Ecosystem::Ruby,
+            ]
+            .into_iter()
+            .find(|ecosystem| ecosystem.to_string() == ecosystem_name)?;
+            return Some(self.ecosystems.contains(&ecosystem));
+        }
+        if let Some(name) = name.strip_prefix("forge.") {
+            return Some(forge_name(self.forge) == name);
+        }
+        None
+    }
+}
+
+fn forge_name(forge: VCSForge) -> &'static str {
+    match forge {
+        VCSForge::GitHub => "github",
+        VCSForge::Codeberg => "codeberg",
+        VCSForge::GitLab => "gitlab",
+        VCSForge::Forgejo => "forgejo",
+    }
+}
+
+/// Renders `{{variable}}`, `{{#if condition}}`, `{{else}}`, and `{{/if}}` tags.
+/// Anything else in braces (e.g. GitHub Actions expressions like `${{ github.ref }}`, or `{{msg}}` in a Vue template) is left untouched.
+pub(crate) fn render_template(
+    template: &str,
+    template_variables: &TemplateVariables,
+) -> RepoResult<String> {
+    let values = template_variables.values();
+    let tag = static_regex!(r"\{\{(#if [a-z_.]+|else|/if|[a-z_]+)\}\}");
+
+    let mut rendered = String::with_capacity(template.len());
+    // Each entry is (whether the parent is active, whether the current branch is active).
+    let mut conditionals: Vec<(bool, bool)> = vec![];
+    let is_active = |conditionals: &Vec<(bool, bool)>| {
+        conditionals
+            .last()
+            .is_none_or(|(parent_active, active)| *parent_active && *active)
+    };
+    let mut previous_end = 0;
+    for captures in tag.captures_iter(template) {
+        let whole_match = captures.get(0).unwrap();
+        if is_active(&conditionals) {
+            rendered.push_str(&template[previous_end..whole_match.start()]);
+        }
+        previous_end = whole_match.end();
+
+        let tag_contents = &captures[1];
+        if let Some(condition_name) = tag_contents.strip_prefix("#if ") {
+            let Some(condition) = template_variables.condition(condition_name) else {
+                return Err(RepoError::user_error(format!(
+                    "Unknown template condition: {}",
+                    condition_name
+                )));
+            };
+            conditionals.push((is_active(&conditionals), condition));
+        } else if tag_contents == "else" {
+            let Some((_, active)) = conditionals.last_mut() else {
+                return Err(RepoError::user_error(
+                    "Template has an `{{else}}` outside of an `{{#if …}}`.",
+                ));
+            };
+            *active = !*active;
+        } else if tag_contents == "/if" {
+            if conditionals.pop().is_none() {
+                return Err(RepoError::user_error(
+                    "Template has an unmatched `{{/if}}`.",
+                ));
+            }
+        } else {
+            if is_active(&conditionals) {
+                rendered.push_str(
+                    values
+                        .get(tag_contents)
+                        .map_or(whole_match.as_str(), |value| value.as_str()),
+                );
+            }
+        }
+    }
+    if !conditionals.is_empty() {
+        return Err(RepoError::user_error(
+            "Template has an unterminated `{{#if …}}`.",
+        ));
+    }
+    rendered.push_str(&template[previous_end..]);
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        ecosystem::Ecosystem,
+        forge::VCSForge,
+        template::{render_template, TemplateVariables},
+    };
+
+    #[test]
+    fn test_render_template() {
+        let template_variables = TemplateVariables {
+            project_name: "example".to_owned(),
+            ecosystems: vec![Ecosystem::Rust],
+            node_version: "24".to_owned(),
+            rust_toolchain_channel: "1.90.0".to_owned(),
+            forge: VCSForge::Codeberg,
+        };
+        assert_eq!(
+            render_template(
+                "{{project_name}}: node {{node_version}}{{#if ecosystem.rust}}, rust {{rust_toolchain_channel}}{{/if}}{{#if ecosystem.javascript}}, js{{else}}{{#if forge.github}}, github{{else}}, {{forge}}{{/if}}{{/if}} ${{ github.ref }}",
+                &template_variables
+            )
+            .unwrap(),
+            "example: node 24, rust 1.90.0, codeberg ${{ github.ref }}"
+        );
+        assert!(render_template("{{#if ecosystem.rust}}", &template_variables).is_err());
+        assert_eq!(
+            render_template(
+                "<p>{{msg}}</p>{{#if ecosystem.rust}}<p>{{ project_name }}</p>{{/if}}",
+                &template_variables
+            )
+            .unwrap(),
+            "<p>{{msg}}</p><p>{{ project_name }}</p>"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::{
+    fs::{create_dir_all, exists, read, remove_file, write},
+    io,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, FromArgMatches};
+
+use crate::common::{
+    boilerplate_lockfile::BoilerplateLockfile,
+    error::{RepoError, RepoResult},
+    structured_merge::{
+        merge_jsonc, merge_toml, ArrayMergePolicy, ConflictMergePolicy, MergePolicies,
+    },
+    template::TemplateVariables,
+    template_file::{
+        TemplateFile, TemplateFileArgs, TemplateFileCommand, TemplateFileCreateArgs,
+        TemplateFileCreateFollowup,
+    },
+};
+
+pub(crate) struct TemplateBundleFile<'a> {
+    pub(crate) template_file: TemplateFile<'a>,
+    /// Unix permissions (e.g. `0o755` for scripts). Ignored on other platforms.
+    pub(crate) mode: Option<u32>,
+}
+
+/// Keys to merge into a JSON/JSONC or TOML config file, which is created if it does not exist.
+/// Existing values are kept, so that patches never overwrite the user's config.
+pub(crate) struct ConfigPatch {
+    pub(crate) relative_path: PathBuf,
+    pub(crate) patch: &'static str,
+}
+
+/// Several template files (and config patches) that are added together, e.g. a script and the config entry that runs it.
+pub(crate) struct TemplateBundle<'a> {
+    pub(crate) files: Vec<TemplateBundleFile<'a>>,
+    pub(crate) config_patches: Vec<ConfigPatch>,
+}
+
+struct PendingWrite<'a> {
+    relative_path: &'a Path,
+    bytes: Vec<u8>,
+    mode: Option<u32>,
+    /// The contents before writing, used to roll back.
+    previous: Option<Vec<u8>>,
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::{fs::set_permissions, os::unix::fs::PermissionsExt};
+
+    set_permissions(path, PermissionsExt::from_mode(mode))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+fn apply_write(pending_write: &PendingWrite) -> io::Result<()> {
+    if let Some(parent) = pending_write.relative_path.parent() {
+        create_dir_all(parent)?;
+    }
+    write(pending_write.relative_path, &pending_write.bytes)?;
+    if let Some(mode) = pending_write.mode {
+        set_mode(pending_write.relative_path, mode)?;
+    }
+    Ok(())
+}
+
+/// Restores the previous contents of written files (or removes them if they did not exist), in reverse order.
+fn roll_back(written: &[&PendingWrite]) {
+    for pending_write in written.iter().rev() {
+        let path = pending_write.relative_path.to_string_lossy();
+        let result = match &pending_write.previous {
+            Some(previous) => write(pending_write.relative_path, previous),
+            None => remove_file(pending_write.relative_path),
+        };
+        match result {
+            Ok(()) => eprintln!("Rolled back: {}", path),
+            Err(e) => eprintln!("⚠️ Could not roll back ({}): {}", path, e),
+        }
+    }
+}
+
+fn merge_config(
+    relative_path: &Path,
+    existing: &str,
+    patch: &str,
+    merge_policies: MergePolicies,
+) -> RepoResult<String> {
+    match relative_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("json" | "jsonc") => merge_jsonc(existing, patch, merge_policies),
+        Some("toml") => merge_toml(existing, patch, merge_policies),
+        _ => Err(RepoError::internal(format!(
+            "Config patches are only supported for JSON/JSONC and TOML files: {}",
+            relative_path.to_string_lossy()
+        ))),
+    }
+}
+
+impl TemplateBundle<'_> {
+    pub(crate) fn handle_command<CustomAddArgs: Args + FromArgMatches>(
+        &self,
+        template_file_args: TemplateFileArgs<CustomAddArgs>,
+    ) -> RepoResult<()> {
+        match template_file_args.command {
+            TemplateFileCommand::Add(template_file_create_args) => {
+                self.create(template_file_create_args)
+            }
+            TemplateFileCommand::Edit => {
+                for bundle_file in &self.files {
+                    bundle_file.template_file.open_for_editing()?;
+                }
+                Ok(())
+            }
+            TemplateFileCommand::Reveal => match self.files.first() {
+                Some(bundle_file) => bundle_file.template_file.reveal(),
+                None => Ok(()),
+            },
+            TemplateFileCommand::Diff(template_variable_args) => {
+                for bundle_file in &self.files {
+                    bundle_file
+                        .template_file
+                        .diff(template_variable_args.clone())?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Renders all files and patches before writing anything, and fails without writing if any file exists (unless `--overwrite` or `--merge` is passed).
+    /// If a write fails, the files written so far are rolled back.
+    fn create<CustomAddArgs: Args + FromArgMatches>(
+        &self,
+        template_file_create_args: TemplateFileCreateArgs<CustomAddArgs>,
+    ) -> RepoResult<()> {
+        let mut existing_paths = vec![];
+        for bundle_file in &self.files {
+            if exists(&bundle_file.template_file.relative_path)? {
+                existing_paths.push(bundle_file.template_file.relative_path.to_string_lossy());
+            }
+        }
+        if !existing_paths.is_empty()
+            && !template_file_create_args.overwrite
+            && !template_file_create_args.merge
+        {
+            return Err(RepoError::user_error(format!(
+                "Files already exist (pass `--overwrite` to overwrite, or `--merge` to merge). No files were written: {}",
+                existing_paths.join(", ")
+            )));
+        }
+
+        let template_variables = TemplateVariables::from_args_or_detect(
+            &template_file_create_args.template_variable_args,
+        );
+        let merge_policies = template_file_create_args.merge_policies();
+        let mut pending_writes = vec![];
+        let mut rendered = vec![];
+        for bundle_file in &self.files {
+            let template_file = &bundle_file.template_file;
+            let rendered_bytes = template_file.rendered_bytes(&template_variables)?;
+            let previous = template_file.current_bytes()?;
+            let bytes = match &previous {
+                Some(previous) if template_file_create_args.merge => template_file
+                    .merge(
+                        &String::from_utf8_lossy(previous),
+                        &rendered_bytes,
+                        merge_policies,
+                    )?
+                    .into_bytes(),
+                _ => rendered_bytes.clone(),
+            };
+            pending_writes.push(PendingWrite {
+                relative_path: &template_file.relative_path,
+                bytes,
+                mode: bundle_file.mode,
+                previous,
+            });
+            rendered.push((&template_file.relative_path, rendered_bytes));
+        }
+        for config_patch in &self.config_patches {
+            let previous = if exists(&config_patch.relative_path)? {
+                Some(read(&config_patch.relative_path)?)
+            } else {
+                None
+            };
+            let bytes = match &previous {
+                Some(previous) => merge_config(
+                    &config_patch.relative_path,
+                    &String::from_utf8_lossy(previous),
+                    config_patch.patch,
+                    MergePolicies {
+                        array: ArrayMergePolicy::Keep,
+                        conflict: ConflictMergePolicy::Keep,
+                    },
+                )?
+                .into_bytes(),
+                None => config_patch.patch.as_bytes().to_vec(),
+            };
+            if previous.as_ref() == Some(&bytes) {
+                continue;
+            }
+            pending_writes.push(PendingWrite {
+                relative_path: &config_patch.relative_path,
+                bytes,
+                mode: None,
+                previous,
+            });
+        }
+
+        let mut written = vec![];
+        for pending_write in &pending_writes {
+            if let Err(e) = apply_write(pending_write) {
+                roll_back(&written);
+                return Err(RepoError::file_system(format!(
+                    "Could not write file ({}): {}",
+                    pending_write.relative_path.to_string_lossy(),
+                    e
+                )));
+            }
+            written.push(pending_write);
+        }
+        for pending_write in &pending_writes {
+            let verb = match pending_write.previous {
+                Some(_) => "Updated",
+                None => "Created",
+            };
+            eprintln!(
+                "{}: {}",
+                verb,
+                pending_write.relative_path.to_string_lossy()
+            );
+        }
+        for (relative_path, rendered_bytes) in rendered {
+            BoilerplateLockfile::record(
+                relative_path,
+                &rendered_bytes,
+                &template_file_create_args.template_variable_args,
+            )?;
+        }
+
+        // Unlike single files, bundles are not opened for editing by default.
+        match template_file_create_args.followup {
+            Some(TemplateFileCreateFollowup::Edit) => {
+                self.handle_command::<CustomAddArgs>(TemplateFileArgs {
+                    command: TemplateFileCommand::Edit,
+                })
+            }
+            Some(TemplateFileCreateFollowup::Reveal) => {
+                self.handle_command::<CustomAddArgs>(TemplateFileArgs {
+                    command: TemplateFileCommand::Reveal,
+                })
+            }
+            Some(TemplateFileCreateFollowup::None) | None => Ok(()),
+        }
+    }
+}
@@ -1,15 +1,26 @@
 use std::{
-    fs::{create_dir_all, exists, File},
+    borrow::Cow,
+    env::var_os,
+    fs::{create_dir_all, exists, read, read_dir, read_to_string, File},
     io::Write,
-    path::PathBuf,
-    process::exit,
+    path::{Path, PathBuf},
+    str::from_utf8,
 };
 
 use clap::{Args, FromArgMatches, Subcommand, ValueEnum};
 use edit::edit_file_without_waiting;
 use opener::reveal;
 
-#[derive(Args, Debug)]
+use crate::common::{
+    boilerplate_lockfile::BoilerplateLockfile,
+    error::{RepoError, RepoResult},
+    structured_merge::{
+        merge_jsonc, merge_toml, ArrayMergePolicy, ConflictMergePolicy, MergePolicies,
+    },
+    template::{render_template, TemplateVariableArgs, TemplateVariables},
+};
+
+#[derive(Args, Clone, Debug)]
 pub(crate) struct TemplateFileArgs<CustomAddArgs: Args + FromArgMatches = BlankArgs> {
     #[command(subcommand)]
     pub(crate) command: TemplateFileCommand<CustomAddArgs>,
@@ -20,45 +31,268 @@ pub(crate) enum TemplateFileCommand<CustomAddArgs: Args + FromArgMatches = Blank
     Add(TemplateFileCreateArgs<CustomAddArgs>),
     Edit,
     Reveal,
+    /// Show a unified diff between the file on disk and the current template.
+    Diff(TemplateVariableArgs),
 }
 
 #[derive(Args, Clone, Debug)]
 pub(crate) struct TemplateFileCreateArgs<CustomAddArgs: Args + FromArgMatches = BlankArgs> {
     #[clap(long)]
-    followup: Option<TemplateFileCreateFollowup>,
+    pub(crate) followup: Option<TemplateFileCreateFollowup>,
     #[clap(long)]
-    overwrite: bool,
+    pub(crate) overwrite: bool,
+    /// Merge the template's keys into an existing JSON/JSONC or TOML file, preserving its comments and formatting.
+    #[clap(long, conflicts_with = "overwrite")]
+    pub(crate) merge: bool,
+    /// How to merge arrays that are in both the existing file and the template. Defaults to `union`.
+    #[clap(long, requires = "merge")]
+    array_policy: Option<ArrayMergePolicy>,
+    /// How to merge values that differ between the existing file and the template. Defaults to `keep`.
+    #[clap(long, requires = "merge")]
+    conflict_policy: Option<ConflictMergePolicy>,
+    #[clap(flatten)]
+    pub(crate) template_variable_args: TemplateVariableArgs,
     #[clap(flatten)]
     pub(crate) custom_args: CustomAddArgs,
 }
 
+impl<CustomAddArgs: Args + FromArgMatches> TemplateFileCreateArgs<CustomAddArgs> {
+    pub(crate) fn merge_policies(&self) -> MergePolicies {
+        MergePolicies {
+            array: self.array_policy.unwrap_or_default(),
+            conflict: self.conflict_policy.unwrap_or_default(),
+        }
+    }
+}
+
 #[derive(Args, Clone, Debug)]
 pub(crate) struct BlankArgs {}
 
 #[derive(Debug, Clone, ValueEnum)]
-enum TemplateFileCreateFollowup {
+pub(crate) enum TemplateFileCreateFollowup {
     // TODO: support `Open` as a version of `Edit` that doesn't wait.
     Edit,
     Reveal,
     None,
 }
 
+const REPO_TEMPLATES_DIR: &str = "./.config/repo-templates";
+
+/// Folders that can override (or add to) the embedded templates, in order of precedence.
+pub(crate) fn template_override_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![PathBuf::from(REPO_TEMPLATES_DIR)];
+    if let Some(home) = var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".config/repo/templates"));
+    }
+    dirs
+}
+
+/// Strips the leading `./` so that the path can be joined onto a template folder.
+fn template_relative_path(relative_path: &Path) -> &Path {
+    relative_path.strip_prefix(".").unwrap_or(relative_path)
+}
+
 pub(crate) struct TemplateFile<'a> {
     pub(crate) relative_path: PathBuf,
-    pub(crate) bytes: &'a [u8],
+    pub(crate) bytes: Cow<'a, [u8]>,
+}
+
+/// A template from an override folder that does not correspond to any embedded template.
+pub(crate) struct ExtraTemplate {
+    pub(crate) name: String,
+    pub(crate) source_path: PathBuf,
+    pub(crate) template_file: TemplateFile<'static>,
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> RepoResult<()> {
+    for entry in read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// The name used for `repo boilerplate <name>`, e.g. `lint` for `.github/workflows/lint.yaml` or `editorconfig` for `.editorconfig`.
+pub(crate) fn extra_template_name(relative_path: &Path) -> String {
+    let file_name = relative_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let file_name = file_name.trim_start_matches('.');
+    file_name
+        .split_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+        .to_lowercase()
+}
+
+/// Finds templates in the override folders whose paths do not match any of the embedded templates.
+pub(crate) fn discover_extra_templates(
+    embedded_relative_paths: &[PathBuf],
+) -> RepoResult<Vec<ExtraTemplate>> {
+    let embedded_relative_paths: Vec<&Path> = embedded_relative_paths
+        .iter()
+        .map(|path| template_relative_path(path))
+        .collect();
+    let mut extra_templates: Vec<ExtraTemplate> = vec![];
+    for dir in template_override_dirs() {
+        if !exists(&dir).unwrap_or(false) {
+            continue;
+        }
+        let mut files = vec![];
+        collect_files(&dir, &mut files)?;
+        files.sort();
+        for source_path in files {
+            let relative_path = source_path.strip_prefix(&dir).unwrap_or(&source_path);
+            if embedded_relative_paths.contains(&relative_path)
+                || extra_templates.iter().any(|extra_template| {
+                    template_relative_path(&extra_template.template_file.relative_path)
+                        == relative_path
+                })
+            {
+                continue;
+            }
+            extra_templates.push(ExtraTemplate {
+                name: extra_template_name(relative_path),
+                template_file: TemplateFile {
+                    relative_path: Path::new(".").join(relative_path),
+                    bytes: Cow::Owned(read(&source_path)?),
+                },
+                source_path,
+            });
+        }
+    }
+    Ok(extra_templates)
+}
+
+/// Returns a similarity ratio between 0 and 1 for the lines of two (possibly non-UTF-8) files.
+pub(crate) fn text_similarity(a: &[u8], b: &[u8]) -> f32 {
+    let (a, b) = (String::from_utf8_lossy(a), String::from_utf8_lossy(b));
+    let num_lines = a.split_inclusive('\n').count() + b.split_inclusive('\n').count();
+    if num_lines == 0 {
+        return 1.0;
+    }
+    let num_deleted = diffy::create_patch(&a, &b)
+        .hunks()
+        .iter()
+        .flat_map(|hunk| hunk.lines())
+        .filter(|line| matches!(line, diffy::Line::Delete(_)))
+        .count();
+    let num_unchanged = a.split_inclusive('\n').count() - num_deleted;
+    2.0 * num_unchanged as f32 / num_lines as f32
 }
 
 impl TemplateFile<'_> {
+    /// Returns the first matching file from the override folders, if any.
+    pub(crate) fn override_path(&self) -> Option<PathBuf> {
+        let relative_path = template_relative_path(&self.relative_path);
+        template_override_dirs()
+            .into_iter()
+            .map(|dir| dir.join(relative_path))
+            .find(|path| exists(path).unwrap_or(false))
+    }
+
+    /// Renders the template (or its override). Binary templates are returned verbatim.
+    pub(crate) fn rendered_bytes(
+        &self,
+        template_variables: &TemplateVariables,
+    ) -> RepoResult<Vec<u8>> {
+        let template_bytes = match self.override_path() {
+            Some(override_path) => Cow::Owned(read(override_path)?),
+            None => Cow::Borrowed(&*self.bytes),
+        };
+        Ok(match from_utf8(&template_bytes) {
+            Ok(template) => render_template(template, template_variables)?.into_bytes(),
+            Err(_) => template_bytes.into_owned(),
+        })
+    }
+
+    /// Returns `None` if the file does not exist.
+    pub(crate) fn current_bytes(&self) -> RepoResult<Option<Vec<u8>>> {
+        if !exists(&self.relative_path)? {
+            return Ok(None);
+        }
+        Ok(Some(read(&self.relative_path)?))
+    }
+
+    /// Returns a unified diff from the file on disk to the rendered template, or `None` if they match.
+    pub(crate) fn unified_diff(
+        &self,
+        template_variables: &TemplateVariables,
+    ) -> RepoResult<Option<String>> {
+        let Some(current_bytes) = self.current_bytes()? else {
+            return Err(RepoError::user_error(format!(
+                "File does not exist (use `add` to create it): {}",
+                self.relative_path.to_string_lossy()
+            )));
+        };
+        let rendered_bytes = self.rendered_bytes(template_variables)?;
+        if current_bytes == rendered_bytes {
+            return Ok(None);
+        }
+        let current = String::from_utf8_lossy(&current_bytes);
+        let rendered = String::from_utf8_lossy(&rendered_bytes);
+        let path = self.relative_path.to_string_lossy();
+        Ok(Some(
+            diffy::DiffOptions::new()
+                .set_original_filename(path.to_string())
+                .set_modified_filename(format!("{} (template)", path))
+                .create_patch(&current, &rendered)
+                .to_string(),
+        ))
+    }
+
+    pub(crate) fn diff(&self, template_variable_args: TemplateVariableArgs) -> RepoResult<()> {
+        let template_variables = TemplateVariables::from_args_or_detect(&template_variable_args);
+        match self.unified_diff(&template_variables)? {
+            Some(unified_diff) => print!("{}", unified_diff),
+            None => eprintln!(
+                "File matches the template: {}",
+                self.relative_path.to_string_lossy()
+            ),
+        }
+        Ok(())
+    }
+
     pub(crate) fn handle_command<CustomAddArgs: Args + FromArgMatches>(
         &self,
         template_file_args: TemplateFileArgs<CustomAddArgs>,
-    ) {
+    ) -> RepoResult<()> {
         match template_file_args.command {
             TemplateFileCommand::Add(template_file_create_args) => {
-                self.create(template_file_create_args);
+                self.create(template_file_create_args)
             }
             TemplateFileCommand::Edit => self.open_for_editing(),
             TemplateFileCommand::Reveal => self.reveal(),
+            TemplateFileCommand::Diff(template_variable_args) => self.diff(template_variable_args),
+        }
+    }
+
+    pub(crate) fn merge(
+        &self,
+        existing: &str,
+        rendered_bytes: &[u8],
+        merge_policies: MergePolicies,
+    ) -> RepoResult<String> {
+        let Ok(template) = from_utf8(rendered_bytes) else {
+            return Err(RepoError::user_error(
+                "`--merge` is not supported for binary templates.",
+            ));
+        };
+        match self
+            .relative_path
+            .extension()
+            .and_then(|extension| extension.to_str())
+        {
+            Some("json" | "jsonc") => merge_jsonc(existing, template, merge_policies),
+            Some("toml") => merge_toml(existing, template, merge_policies),
+            _ => Err(RepoError::user_error(format!(
+                "`--merge` is only supported for JSON/JSONC and TOML files: {}",
+                self.relative_path.to_string_lossy()
+            ))),
         }
     }
 
@@ -66,74 +300,142 @@ impl TemplateFile<'_> {
     fn create<CustomAddArgs: Args + FromArgMatches>(
         &self,
         template_file_write_args: TemplateFileCreateArgs<CustomAddArgs>,
-    ) {
-        if exists(&self.relative_path).expect("Could not access file system.") {
-            if template_file_write_args.overwrite {
+    ) -> RepoResult<()> {
+        let mut merge_into: Option<String> = None;
+        if exists(&self.relative_path)? {
+            if template_file_write_args.merge {
+                eprintln!(
+                    "Merging template into existing file due to `--merge` flag: {}",
+                    self.relative_path.to_string_lossy()
+                );
+                merge_into = Some(read_to_string(&self.relative_path)?);
+            } else if template_file_write_args.overwrite {
                 eprintln!(
                     "Overwriting file due to `--overwrite` flag: {}",
                     self.relative_path.to_string_lossy()
                 );
             } else {
-                eprintln!(
+                return Err(RepoError::user_error(format!(
                     "File already exists (pass `--overwrite` to overwrite): {}",
                     self.relative_path.to_string_lossy()
-                );
-                exit(1);
+                )));
             }
         }
 
-        let Some(_) = self.relative_path.parent().map(create_dir_all) else {
-            eprintln!(
-                "Unable to create directory for file: {}",
+        let template_variables = TemplateVariables::from_args_or_detect(
+            &template_file_write_args.template_variable_args,
+        );
+        if let Some(override_path) = self.override_path() {
+            eprintln!("Using template from: {}", override_path.to_string_lossy());
+        }
+        let rendered_bytes = self.rendered_bytes(&template_variables)?;
+        let bytes = match merge_into {
+            Some(existing) => self
+                .merge(
+                    &existing,
+                    &rendered_bytes,
+                    template_file_write_args.merge_policies(),
+                )?
+                .into_bytes(),
+            None => rendered_bytes.clone(),
+        };
+        self.write(&bytes)?;
+        BoilerplateLockfile::record(
+            &self.relative_path,
+            &rendered_bytes,
+            &template_file_write_args.template_variable_args,
+        )?;
+
+        match template_file_write_args.followup {
+            Some(TemplateFileCreateFollowup::Edit) => self.open_for_editing(),
+            Some(TemplateFileCreateFollowup::Reveal) => self.reveal(),
+            Some(TemplateFileCreateFollowup::None) => Ok(()),
+            None => self.open_for_editing(),
+        }
+    }
+
+    pub(crate) fn write(&self, bytes: &[u8]) -> RepoResult<()> {
+        if let Some(Err(e)) = self.relative_path.parent().map(create_dir_all) {
+            return Err(RepoError::file_system(format!(
+                "Unable to create directory for file ({}): {}",
                 self.relative_path.to_string_lossy(),
-            );
-            exit(1);
+                e
+            )));
         };
 
         let Ok(mut file) = File::create(&self.relative_path) else {
-            eprintln!(
+            return Err(RepoError::file_system(format!(
                 "Could not open file to write: {}",
                 self.relative_path.to_string_lossy()
-            );
-            exit(1);
+            )));
         };
-        let Ok(()) = file.write_all(self.bytes) else {
-            eprintln!(
+        let Ok(()) = file.write_all(bytes) else {
+            return Err(RepoError::file_system(format!(
                 "Unable to write file: {}",
                 self.relative_path.to_string_lossy()
-            );
-            exit(1);
+            )));
         };
+        Ok(())
+    }
 
-        match template_file_write_args.followup {
-            Some(TemplateFileCreateFollowup::Edit) => self.open_for_editing(),
-            Some(TemplateFileCreateFollowup::Reveal) => {
-                self.reveal();
-            }
-            Some(TemplateFileCreateFollowup::None) => {}
-            None => {
-                self.open_for_editing();
-            }
-        };
+    /// Writes the rendered template unless the file already exists (e.g. for `repo boilerplate init`).
+    /// Pass `record_in_lockfile` for boilerplate that should be upgradeable (as opposed to scaffolding that is expected to diverge, like `src/main.rs`).
+    pub(crate) fn add_if_missing(
+        &self,
+        template_variables: &TemplateVariables,
+        template_variable_args: &TemplateVariableArgs,
+        record_in_lockfile: bool,
+    ) -> RepoResult<()> {
+        if exists(&self.relative_path)? {
+            eprintln!(
+                "Skipping file that already exists: {}",
+                self.relative_path.to_string_lossy()
+            );
+            return Ok(());
+        }
+        let rendered_bytes = self.rendered_bytes(template_variables)?;
+        self.write(&rendered_bytes)?;
+        if record_in_lockfile {
+            BoilerplateLockfile::record(
+                &self.relative_path,
+                &rendered_bytes,
+                template_variable_args,
+            )?;
+        }
+        eprintln!("Created: {}", self.relative_path.to_string_lossy());
+        Ok(())
     }
 
-    pub fn open_for_editing(&self) {
+    pub fn open_for_editing(&self) -> RepoResult<()> {
         let Ok(_) = edit_file_without_waiting(&self.relative_path) else {
-            eprintln!(
+            return Err(RepoError::tool_failed(format!(
                 "Could not open file for editing: {}",
                 self.relative_path.to_string_lossy()
-            );
-            exit(1);
+            )));
         };
+        Ok(())
     }
 
-    pub fn reveal(&self) {
+    pub fn reveal(&self) -> RepoResult<()> {
         let Ok(()) = reveal(&self.relative_path) else {
-            eprintln!(
-                "Could not open file for editing {}",
+            return Err(RepoError::tool_failed(format!(
+                "Could not reveal file: {}",
                 self.relative_path.to_string_lossy()
-            );
-            exit(1);
+            )));
         };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::template_file::text_similarity;
+
+    #[test]
+    fn test_text_similarity() {
+        assert_eq!(text_similarity(b"", b""), 1.0);
+        assert_eq!(text_similarity(b"a\nb\n", b"a\nb\n"), 1.0);
+        assert_eq!(text_similarity(b"a\nb\n", b"c\nd\n"), 0.0);
+        assert_eq!(text_similarity(b"a\nb\nc\n", b"a\nb\nd\n"), 2.0 * 2.0 / 6.0);
     }
 }
@@ -0,0 +1,304 @@
+use std::{
+    borrow::Cow,
+    env::{current_dir, var_os},
+    fs::{canonicalize, create_dir_all, exists, read_to_string, write},
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+};
+
+use printable_shell_command::PrintableShellCommand;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::common::{
+    command::{command_must_get_stdout, spawn_error},
+    config::{Config, TemplatePackConfig, CONFIG_PATH},
+    debug::DebugPrintable,
+    error::{RepoError, RepoResult},
+    structured_merge::{merge_jsonc, ConflictMergePolicy, MergePolicies},
+    template_file::{extra_template_name, ExtraTemplate, TemplateFile},
+    vcs::auto_detect_preferred_vcs_and_repo_root,
+};
+
+/// Templates are read from this folder of the pack, mirroring the paths in the project (like `.config/repo-templates/`).
+const PACK_TEMPLATES_DIR: &str = "templates";
+
+/// Packs are cached as mirror clones in `$XDG_CACHE_HOME/repo/template-packs/` (or `~/.cache/repo/template-packs/`).
+fn cache_dir() -> RepoResult<PathBuf> {
+    if let Some(cache_home) = var_os("XDG_CACHE_HOME") {
+        return Ok(PathBuf::from(cache_home).join("repo/template-packs"));
+    }
+    let Some(home) = var_os("HOME") else {
+        return Err(RepoError::detection_failure(
+            "Could not determine the cache folder (neither `XDG_CACHE_HOME` nor `HOME` is set).",
+        ));
+    };
+    Ok(PathBuf::from(home).join(".cache/repo/template-packs"))
+}
+
+/// Local pack paths in `.config/repo.json` are relative to the repo root (or the current folder outside a repo).
+fn project_root() -> RepoResult<PathBuf> {
+    let current_dir = current_dir()?;
+    let root = auto_detect_preferred_vcs_and_repo_root(&current_dir)
+        .map(|(_, root)| PathBuf::from(root))
+        .unwrap_or(current_dir);
+    Ok(canonicalize(&root).unwrap_or(root))
+}
+
+/// The path from `from` to `to` (both absolute), e.g. `../acme-templates`.
+fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let path: PathBuf = (common..from.len())
+        .map(|_| Component::ParentDir)
+        .chain(to[common..].iter().copied())
+        .collect();
+    if path.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        path
+    }
+}
+
+/// A local `source` (as passed to `pack add`) is stored relative to the repo root, so that the config does not depend on the working directory.
+fn config_source(source: &str) -> RepoResult<String> {
+    if !exists(source)? {
+        return Ok(source.to_owned());
+    }
+    let path = relative_path(&project_root()?, &canonicalize(source)?);
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Local paths are resolved from the repo root and cloned using their absolute path, so that the cache does not depend on the working directory.
+fn clone_source(source: &str) -> RepoResult<String> {
+    let path = project_root()?.join(source);
+    if exists(&path)? {
+        return Ok(canonicalize(path)?.to_string_lossy().to_string());
+    }
+    Ok(source.to_owned())
+}
+
+fn git(git_dir: &Path, args: &[&str]) -> RepoResult<String> {
+    let mut command = PrintableShellCommand::new("git");
+    command.arg("--git-dir").arg(git_dir).args(args);
+    command_must_get_stdout(command)
+}
+
+/// Like `git cat-file blob`, but returns the bytes as-is (templates may be binary).
+fn git_blob(git_dir: &Path, object: &str) -> RepoResult<Vec<u8>> {
+    let mut command = PrintableShellCommand::new("git");
+    command
+        .arg("--git-dir")
+        .arg(git_dir)
+        .args(["cat-file", "blob", object]);
+    command.debug_print();
+    let output = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|error| spawn_error(&command, error))?;
+    if !output.status.success() {
+        return Err(RepoError::tool_failed(format!(
+            "Could not read `{}` from template pack.",
+            object
+        ))
+        .with_stderr(String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(output.stdout)
+}
+
+/// Clones the pack into the cache (if needed), and fetches if `rev` is not available yet.
+/// Returns the git folder of the cached clone.
+fn cached_pack(source: &str, rev: Option<&str>) -> RepoResult<PathBuf> {
+    let clone_source = clone_source(source)?;
+    let git_dir = cache_dir()?.join(format!("{:x}", Sha256::digest(clone_source.as_bytes())));
+    if !exists(&git_dir)? {
+        create_dir_all(cache_dir()?)?;
+        eprintln!("Cloning template pack: {}", source);
+        let mut command = PrintableShellCommand::new("git");
+        command
+            .args(["clone", "--quiet", "--mirror", &clone_source])
+            .arg(&git_dir);
+        command_must_get_stdout(command)?;
+    } else if rev.is_none_or(|rev| resolve_commit(&git_dir, rev).is_err()) {
+        eprintln!("Fetching template pack: {}", source);
+        git(&git_dir, &["fetch", "--quiet", "--prune", "origin"])?;
+    }
+    Ok(git_dir)
+}
+
+fn resolve_commit(git_dir: &Path, rev: &str) -> RepoResult<String> {
+    git(
+        git_dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", rev),
+        ],
+    )
+    .map_err(|_| {
+        RepoError::user_error(format!("Could not find revision in template pack: {}", rev))
+    })
+}
+
+/// Paths of the templates in the pack at the given commit, relative to its `templates/` folder.
+fn pack_template_paths(git_dir: &Path, commit: &str) -> RepoResult<Vec<String>> {
+    let files = git(
+        git_dir,
+        &[
+            "ls-tree",
+            "-r",
+            "--name-only",
+            commit,
+            "--",
+            &format!("{}/", PACK_TEMPLATES_DIR),
+        ],
+    )?;
+    Ok(files
+        .lines()
+        .filter_map(|line| line.strip_prefix(&format!("{}/", PACK_TEMPLATES_DIR)))
+        .map(str::to_owned)
+        .collect())
+}
+
+/// The default pack name, e.g. `acme-templates` for `https://example.com/acme/acme-templates.git`.
+fn default_pack_name(source: &str) -> String {
+    let source = source.trim_end_matches('/');
+    let name = source.rsplit(['/', ':']).next().unwrap_or(source);
+    name.strip_suffix(".git").unwrap_or(name).to_owned()
+}
+
+/// Sets `templatePacks.<name>` in `.config/repo.json`, preserving the rest of the file.
+fn write_pack_config(name: &str, source: &str, commit: &str) -> RepoResult<()> {
+    // Built by hand (rather than using `json!(…)`) to keep `source` before `commit`.
+    let [name, source, commit] = [name, source, commit].map(|value| Value::from(value).to_string());
+    let template = format!(
+        "{{\n  \"templatePacks\": {{\n    {}: {{\n      \"source\": {},\n      \"commit\": {}\n    }}\n  }}\n}}\n",
+        name, source, commit
+    );
+    let contents = if exists(CONFIG_PATH)? {
+        merge_jsonc(
+            &read_to_string(CONFIG_PATH)?,
+            &template,
+            MergePolicies {
+                conflict: ConflictMergePolicy::Template,
+                ..MergePolicies::default()
+            },
+        )?
+    } else {
+        create_dir_all(Path::new(CONFIG_PATH).parent().unwrap_or(Path::new(".")))?;
+        template
+    };
+    write(CONFIG_PATH, contents)?;
+    Ok(())
+}
+
+/// Caches the pack and pins it in `.config/repo.json` (at `rev`, or the default branch).
+pub(crate) fn add_template_pack(
+    source: &str,
+    rev: Option<&str>,
+    name: Option<String>,
+) -> RepoResult<()> {
+    let source = &config_source(source)?;
+    let name = name.unwrap_or_else(|| default_pack_name(source));
+    if name.is_empty() || name.contains('/') {
+        return Err(RepoError::user_error(format!(
+            "Invalid template pack name (pass `--name`): {}",
+            name
+        )));
+    }
+    let git_dir = cached_pack(source, rev)?;
+    let commit = resolve_commit(&git_dir, rev.unwrap_or("HEAD"))?;
+    let template_paths = pack_template_paths(&git_dir, &commit)?;
+    if template_paths.is_empty() {
+        return Err(RepoError::user_error(format!(
+            "Template pack has no files in its `{}/` folder: {}",
+            PACK_TEMPLATES_DIR, source
+        )));
+    }
+    write_pack_config(&name, source, &commit)?;
+    println!("Pinned template pack `{}` to: {}", name, commit);
+    for template_path in template_paths {
+        println!(
+            "{}/{} → ./{}",
+            name,
+            extra_template_name(Path::new(&template_path)),
+            template_path
+        );
+    }
+    Ok(())
+}
+
+fn pack_templates(name: &str, pack: &TemplatePackConfig) -> RepoResult<Vec<ExtraTemplate>> {
+    let git_dir = cached_pack(&pack.source, Some(&pack.commit))?;
+    let mut templates = vec![];
+    for template_path in pack_template_paths(&git_dir, &pack.commit)? {
+        let object = format!("{}:{}/{}", pack.commit, PACK_TEMPLATES_DIR, template_path);
+        templates.push(ExtraTemplate {
+            name: format!(
+                "{}/{}",
+                name,
+                extra_template_name(Path::new(&template_path))
+            ),
+            source_path: PathBuf::from(format!("{} ({})", pack.source, object)),
+            template_file: TemplateFile {
+                relative_path: Path::new(".").join(&template_path),
+                bytes: Cow::Owned(git_blob(&git_dir, &object)?),
+            },
+        });
+    }
+    Ok(templates)
+}
+
+/// Templates from all packs in `.config/repo.json`, named `<pack>/<template>`.
+pub(crate) fn template_pack_templates() -> RepoResult<Vec<ExtraTemplate>> {
+    let mut templates = vec![];
+    for (name, pack) in &Config::get()?.template_packs {
+        templates.extend(pack_templates(name, pack)?);
+    }
+    Ok(templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::common::template_pack::{default_pack_name, relative_path};
+
+    #[test]
+    fn test_default_pack_name() {
+        assert_eq!(
+            default_pack_name("https://example.com/acme/acme-templates.git"),
+            "acme-templates"
+        );
+        assert_eq!(default_pack_name("git@example.com:acme/web.git"), "web");
+        assert_eq!(
+            default_pack_name("../shared-templates/"),
+            "shared-templates"
+        );
+    }
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(
+                Path::new("/work/project"),
+                Path::new("/work/acme-templates")
+            ),
+            PathBuf::from("../acme-templates")
+        );
+        assert_eq!(
+            relative_path(
+                Path::new("/work/project"),
+                Path::new("/work/project/vendor/pack")
+            ),
+            PathBuf::from("vendor/pack")
+        );
+        assert_eq!(
+            relative_path(Path::new("/work/project"), Path::new("/work/project")),
+            PathBuf::from(".")
+        );
+    }
+}
@@ -3,7 +3,10 @@ use std::{env::current_dir, fmt::Display, fs::exists, path::Path};
 use clap::ValueEnum;
 use printable_shell_command::PrintableShellCommand;
 
-use super::inference::get_stdout;
+use super::{
+    error::{RepoError, RepoResult},
+    inference::get_stdout,
+};
 
 #[derive(Debug, Copy, Clone, ValueEnum)]
 pub(crate) enum VcsKind {
@@ -65,14 +68,36 @@ pub(crate) fn auto_detect_preferred_vcs_and_repo_root(
     None
 }
 
-pub fn vcs_or_infer(vcs: Option<VcsKind>) -> Result<VcsKind, String> {
+/// Returns the URL of the `origin` remote, using `git` (which also works for colocated `jj` repos) or `jj`.
+pub(crate) fn auto_detect_remote_url() -> Option<String> {
+    {
+        let mut git_command = PrintableShellCommand::new("git");
+        git_command.args(["remote", "get-url", "origin"]);
+        if let Some(url) = get_stdout(git_command) {
+            return Some(url);
+        }
+    }
+    {
+        let mut jj_command = PrintableShellCommand::new("jj");
+        jj_command.args(["git", "remote", "list"]);
+        if let Some(remotes) = get_stdout(jj_command) {
+            for line in remotes.lines() {
+                if let Some(url) = line.strip_prefix("origin ") {
+                    return Some(url.trim().to_owned());
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn vcs_or_infer(vcs: Option<VcsKind>) -> RepoResult<VcsKind> {
     Ok(match vcs {
         Some(vcs_kind) => vcs_kind,
         None => {
-            let Some((vcs_kind, _)) =
-                auto_detect_preferred_vcs_and_repo_root(&current_dir().unwrap())
+            let Some((vcs_kind, _)) = auto_detect_preferred_vcs_and_repo_root(&current_dir()?)
             else {
-                return Err("No VCS specified or found.".to_owned());
+                return Err(RepoError::detection_failure("No VCS specified or found."));
             };
             vcs_kind
         }
@@ -3,7 +3,9 @@ use std::{fs::exists, path::Path};
 use printable_shell_command::PrintableShellCommand;
 use serde::Deserialize;
 
-use crate::common::package_manager::PACKAGE_JSON_PATH;
+use crate::common::package_manager::{
+    DENO_JSONC_PATH, DENO_JSON_PATH, GEMFILE_PATH, PACKAGE_JSON_PATH,
+};
 
 use super::{inference::get_stdout, vcs::auto_detect_preferred_vcs_and_repo_root};
 
@@ -48,6 +50,17 @@ pub(crate) fn auto_detect_workspace_root(
                 return Some(dir.to_string_lossy().to_string());
             }
         }
+        for deno_json_path in [DENO_JSON_PATH, DENO_JSONC_PATH] {
+            if exists(dir.join(Path::new(deno_json_path))).unwrap_or(false) {
+                return Some(dir.to_string_lossy().to_string());
+            }
+        }
+        {
+            let gemfile_path = dir.join(Path::new(GEMFILE_PATH));
+            if exists(gemfile_path).unwrap_or(false) {
+                return Some(dir.to_string_lossy().to_string());
+            }
+        }
         {
             let go_mod_path = dir.join(Path::new(GO_MOD_PATH));
             if exists(go_mod_path).unwrap_or(false) {
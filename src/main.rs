@@ -5,6 +5,7 @@ mod common;
 use args::get_args;
 use commands::boilerplate::boilerplate as boilerplate_command;
 use commands::publish::publish_command;
+use commands::release::release_command;
 use commands::setup::setup_command;
 use commands::vcs::vcs_command;
 use commands::version::version_command;
@@ -13,23 +14,31 @@ use shadow_rs::shadow;
 
 use crate::commands::dependencies::dependencies_command;
 use crate::commands::print_schema::print_schema;
+use crate::commands::readme_cli_help::readme_cli_help_command;
 
 shadow!(build);
 
 fn main() {
     let args = get_args();
 
-    match args.command {
+    let result = match args.command {
         args::RepoCommand::Version(version_args) => version_command(version_args),
         args::RepoCommand::Publish(publish_args) => publish_command(publish_args),
+        args::RepoCommand::Release(release_args) => release_command(release_args),
         args::RepoCommand::Boilerplate(boilerplate_args) => boilerplate_command(boilerplate_args),
         args::RepoCommand::Setup(setup_args) => setup_command(setup_args),
-        args::RepoCommand::Vcs(vcs_args) => vcs_command(vcs_args).unwrap(),
+        args::RepoCommand::Vcs(vcs_args) => vcs_command(vcs_args),
         args::RepoCommand::Workspace(workspace_args) => workspace_command(workspace_args),
         args::RepoCommand::Dependencies(dependencies_args) => {
-            dependencies_command(dependencies_args).unwrap()
+            dependencies_command(dependencies_args)
+        }
+        args::RepoCommand::ReadmeCliHelp(readme_cli_help_args) => {
+            readme_cli_help_command(readme_cli_help_args)
         }
         args::RepoCommand::PrintSchema(print_schema_args) => print_schema(print_schema_args),
         args::RepoCommand::Completions(_) => panic!("We should have exited earlier."),
+    };
+    if let Err(error) = result {
+        error.exit();
     }
 }
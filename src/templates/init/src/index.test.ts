@@ -0,0 +1,6 @@
+import { expect, test } from "bun:test";
+import { hello } from ".";
+
+test("hello", () => {
+  expect(hello()).toBe("Hello from {{project_name}}!");
+});
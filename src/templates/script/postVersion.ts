@@ -0,0 +1,13 @@
+#!/usr/bin/env -S bun run --
+
+import { stdin } from "node:process";
+import { Readable } from "node:stream";
+
+// Called by `repo version` after the version changes.
+// Run `repo print-schema postVersion` for the format of the JSON input.
+const { command, version } = await new Response(
+  // biome-ignore lint/suspicious/noExplicitAny: `Readable.from(…)` is a valid body at runtime.
+  Readable.from(stdin) as any,
+).json();
+
+console.log(`\`repo version ${command}\` changed the version to: ${version}`);
@@ -0,0 +1,37 @@
+mod common;
+
+use std::{
+    fs::{exists, read_to_string},
+    process::Output,
+};
+
+use common::{git, TempDir};
+
+fn repo_init(dir: &TempDir, args: &[&str]) -> Output {
+    dir.repo(dir)
+        .args(["boilerplate", "init", "--no-setup", "--forge", "github"])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_init_in_existing_repository() {
+    let dir = TempDir::new("init");
+    git(&dir, &["init", "--quiet"]);
+
+    let output = repo_init(&dir, &["--preset", "rust-cli", "--init-vcs", "git"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already a repository"));
+    assert!(read_to_string(dir.join("Cargo.toml"))
+        .unwrap()
+        .contains("[package]"));
+    assert!(exists(dir.join(".github/workflows/CI.yaml")).unwrap());
+    assert!(read_to_string(dir.join(".config/repo-boilerplate.json"))
+        .unwrap()
+        .contains("rust-toolchain.toml"));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    path::Path,
+    process::Output,
+};
+
+use common::TempDir;
+
+fn repo_boilerplate(dir: &TempDir, args: &[&str]) -> Output {
+    dir.repo(dir)
+        .arg("boilerplate")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// Changes the template for `bunfig.toml` using a repo-local override.
+fn set_template(dir: &Path, contents: &str) {
+    write(dir.join(".config/repo-templates/bunfig.toml"), contents).unwrap();
+}
+
+fn bunfig(dir: &Path) -> String {
+    read_to_string(dir.join("bunfig.toml")).unwrap()
+}
+
+#[test]
+fn test_upgrade_merges_template_changes() {
+    let dir = TempDir::new("upgrade");
+    create_dir_all(dir.join(".config/repo-templates")).unwrap();
+    set_template(&dir, "a = 1\nb = 2\nc = 3\n");
+    assert!(
+        repo_boilerplate(&dir, &["bunfig", "add", "--followup", "none"])
+            .status
+            .success()
+    );
+    assert_eq!(bunfig(&dir), "a = 1\nb = 2\nc = 3\n");
+
+    // Unmodified files are replaced by the new template.
+    set_template(&dir, "a = 1\nb = 2\nc = 3\nd = 4\n");
+    assert!(repo_boilerplate(&dir, &["upgrade"]).status.success());
+    assert_eq!(bunfig(&dir), "a = 1\nb = 2\nc = 3\nd = 4\n");
+
+    // Local changes are kept.
+    write(dir.join("bunfig.toml"), "a = 10\nb = 2\nc = 3\nd = 4\n").unwrap();
+    set_template(&dir, "a = 1\nb = 2\nc = 3\nd = 4\ne = 5\n");
+    assert!(repo_boilerplate(&dir, &["upgrade"]).status.success());
+    assert_eq!(bunfig(&dir), "a = 10\nb = 2\nc = 3\nd = 4\ne = 5\n");
+    let output = repo_boilerplate(&dir, &["upgrade"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(up to date)"));
+
+    // Conflicting changes are written with conflict markers.
+    write(
+        dir.join("bunfig.toml"),
+        "a = 10\nb = 20\nc = 3\nd = 4\ne = 5\n",
+    )
+    .unwrap();
+    set_template(&dir, "a = 1\nb = 200\nc = 3\nd = 4\ne = 5\n");
+    assert_eq!(repo_boilerplate(&dir, &["upgrade"]).status.code(), Some(8));
+    let conflicted = bunfig(&dir);
+    assert!(conflicted.contains("<<<<<<<"));
+    assert!(conflicted.contains("b = 20\n"));
+    assert!(conflicted.contains("b = 200\n"));
+}
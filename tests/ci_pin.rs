@@ -0,0 +1,93 @@
+mod common;
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    process::Output,
+};
+
+use common::{git, TempDir};
+
+// Sets up a bare `actions/checkout` repo (with an annotated `v1.0.0` tag and a lightweight `v1.1.0` tag) under `remotes/`, and a project with a workflow that uses it.
+fn set_up_remote_and_project() -> (TempDir, String, String) {
+    let dir = TempDir::new("ci-pin");
+    let source = dir.join("source");
+    create_dir_all(&source).unwrap();
+    git(&source, &["init", "--quiet"]);
+    git(
+        &source,
+        &["commit", "--quiet", "--allow-empty", "-m", "v1.0.0"],
+    );
+    git(&source, &["tag", "--annotate", "v1.0.0", "-m", "v1.0.0"]);
+    let v1_0_0 = git(&source, &["rev-parse", "HEAD"]);
+    git(
+        &source,
+        &["commit", "--quiet", "--allow-empty", "-m", "v1.1.0"],
+    );
+    git(&source, &["tag", "v1.1.0"]);
+    let v1_1_0 = git(&source, &["rev-parse", "HEAD"]);
+    create_dir_all(dir.join("remotes/actions")).unwrap();
+    git(
+        &dir,
+        &[
+            "clone",
+            "--quiet",
+            "--bare",
+            "source",
+            "remotes/actions/checkout",
+        ],
+    );
+
+    create_dir_all(dir.join("project/.github/workflows")).unwrap();
+    write(
+        dir.join("project/.github/workflows/CI.yaml"),
+        "jobs:\n  test:\n    steps:\n      - uses: \"actions/checkout@v1.0.0\" # TODO pin later\n      - uses: ./local-action\n",
+    )
+    .unwrap();
+    (dir, v1_0_0, v1_1_0)
+}
+
+fn repo_ci(dir: &TempDir, args: &[&str]) -> Output {
+    dir.repo(dir.join("project"))
+        .args(["boilerplate", "ci"])
+        .args(args)
+        .arg("--remote-base")
+        .arg(dir.join("remotes"))
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_ci_pin_and_lint() {
+    let (dir, v1_0_0, v1_1_0) = set_up_remote_and_project();
+    let workflow_path = dir.join("project/.github/workflows/CI.yaml");
+
+    let output = repo_ci(&dir, &["lint"]);
+    assert_eq!(output.status.code(), Some(8));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("is not pinned to a commit SHA"));
+
+    assert!(repo_ci(&dir, &["pin"]).status.success());
+    assert_eq!(
+        read_to_string(&workflow_path).unwrap(),
+        format!(
+            "jobs:\n  test:\n    steps:\n      - uses: actions/checkout@{} # v1.0.0\n      - uses: ./local-action\n",
+            v1_0_0
+        )
+    );
+    assert!(repo_ci(&dir, &["lint"]).status.success());
+
+    // A comment that disagrees with the SHA.
+    write(
+        &workflow_path,
+        read_to_string(&workflow_path)
+            .unwrap()
+            .replace("# v1.0.0", "# v1.1.0"),
+    )
+    .unwrap();
+    assert_eq!(repo_ci(&dir, &["lint"]).status.code(), Some(8));
+
+    assert!(repo_ci(&dir, &["pin", "--latest"]).status.success());
+    assert!(read_to_string(&workflow_path)
+        .unwrap()
+        .contains(&format!("actions/checkout@{} # v1.1.0", v1_1_0)));
+    assert!(repo_ci(&dir, &["lint"]).status.success());
+}
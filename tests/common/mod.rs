@@ -0,0 +1,70 @@
+//! Fixtures shared by the integration tests.
+// Each test binary uses a different subset of these.
+#![allow(dead_code)]
+
+use std::{
+    env,
+    fs::{create_dir_all, remove_dir_all},
+    ops::Deref,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// A fresh folder for a test, removed when dropped (including when an assertion fails).
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// The process ID is included so that concurrent runs don't share folders.
+    pub fn new(name: &str) -> Self {
+        let path = env::temp_dir().join(format!("repo-test-{}-{}", name, std::process::id()));
+        let _ = remove_dir_all(&path);
+        create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Runs `repo` in `current_dir`, with `HOME` inside the temp folder so that the user's own templates and config are not used.
+    pub fn repo(&self, current_dir: impl AsRef<Path>) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_repo"));
+        command
+            .current_dir(current_dir)
+            .env("HOME", self.0.join("home"));
+        command
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempDir {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = remove_dir_all(&self.0);
+    }
+}
+
+/// Runs `git` with a fixed identity, and returns its trimmed `stdout`.
+pub fn git(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "git {}: {}",
+        args.join(" "),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap().trim().to_owned()
+}
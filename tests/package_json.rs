@@ -0,0 +1,26 @@
+mod common;
+
+use std::fs::exists;
+
+use common::TempDir;
+
+#[test]
+fn test_non_js_package_manager_is_a_user_error() {
+    let dir = TempDir::new("package-json");
+
+    for package_manager in ["cargo", "deno", "bundler"] {
+        let output = dir
+            .repo(&dir)
+            .args([
+                "boilerplate",
+                "package-json",
+                "--package-manager",
+                package_manager,
+            ])
+            .output()
+            .unwrap();
+        assert_eq!(output.status.code(), Some(2), "{}", package_manager);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Not a JS package manager"));
+    }
+    assert!(!exists(dir.join("package.json")).unwrap());
+}
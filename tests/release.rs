@@ -0,0 +1,111 @@
+mod common;
+
+use std::{
+    env,
+    fs::{create_dir_all, read_to_string, set_permissions, write, Permissions},
+    os::unix::fs::PermissionsExt,
+    path::Path,
+};
+
+use common::{git, TempDir};
+
+// Sets up a `git` repo with a fake `gh` on `PATH` that records its arguments (one per line).
+fn set_up_repo(name: &str) -> TempDir {
+    let dir = TempDir::new(&format!("release-{}", name));
+    create_dir_all(dir.join("fake-bin")).unwrap();
+    let fake_gh = dir.join("fake-bin/gh");
+    write(
+        &fake_gh,
+        "#!/bin/sh\nprintf '%s\\n' \"$@\" > \"$(dirname \"$0\")/gh-args.txt\"\n",
+    )
+    .unwrap();
+    set_permissions(&fake_gh, Permissions::from_mode(0o755)).unwrap();
+    git(&dir, &["init", "--quiet"]);
+    dir
+}
+
+fn commit(dir: &Path, message: &str) {
+    git(
+        dir,
+        &["commit", "--quiet", "--allow-empty", "--message", message],
+    );
+}
+
+fn release_create(dir: &TempDir, args: &[&str]) -> Vec<String> {
+    let path = format!(
+        "{}:{}",
+        dir.join("fake-bin").to_string_lossy(),
+        env::var("PATH").unwrap()
+    );
+    let status = dir
+        .repo(dir)
+        .env("PATH", path)
+        .args(["release", "create", "--forge", "github", "--use-vcs", "git"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    read_to_string(dir.join("fake-bin/gh-args.txt"))
+        .unwrap()
+        .lines()
+        .map(str::to_owned)
+        .collect()
+}
+
+#[test]
+fn test_release_create_with_changelog_notes() {
+    let dir = set_up_repo("changelog");
+    write(
+        dir.join("CHANGELOG.md"),
+        "# Changelog\n\n## v1.1.0\n\n- New feature.\n\n## v1.0.0\n\n- Initial release.\n",
+    )
+    .unwrap();
+    write(dir.join("artifact.txt"), "artifact").unwrap();
+    git(&dir, &["add", "CHANGELOG.md"]);
+    commit(&dir, "Release v1.1.0");
+    git(&dir, &["tag", "v1.1.0"]);
+
+    let args = release_create(&dir, &["artifact.txt"]);
+    assert_eq!(
+        args,
+        [
+            "release",
+            "create",
+            "v1.1.0",
+            "--title",
+            "v1.1.0",
+            "--notes",
+            "- New feature.",
+            "--verify-tag",
+            "--",
+            "artifact.txt"
+        ]
+    );
+}
+
+#[test]
+fn test_release_create_with_commit_notes() {
+    let dir = set_up_repo("commits");
+    commit(&dir, "Initial commit");
+    git(&dir, &["tag", "v0.1.0"]);
+    commit(&dir, "Fix a bug");
+    commit(&dir, "Add a feature");
+    git(&dir, &["tag", "v0.2.0-dev"]);
+
+    let args = release_create(&dir, &[]);
+    assert_eq!(
+        args,
+        [
+            "release",
+            "create",
+            "v0.2.0-dev",
+            "--title",
+            "v0.2.0-dev",
+            "--notes",
+            "- Add a feature",
+            "- Fix a bug",
+            "--verify-tag",
+            "--prerelease"
+        ]
+    );
+}
@@ -0,0 +1,38 @@
+mod common;
+
+use std::fs::{read_to_string, write};
+
+use common::TempDir;
+
+#[test]
+fn test_release_binaries_are_named_after_the_crate() {
+    let dir = TempDir::new("release-binaries");
+    write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"foo\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+    write(dir.join("package.json"), "{ \"name\": \"@acme/foo\" }\n").unwrap();
+
+    let output = dir
+        .repo(&dir)
+        .args([
+            "boilerplate",
+            "release-binaries",
+            "add",
+            "--followup",
+            "none",
+        ])
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let workflow =
+        read_to_string(dir.join(".github/workflows/build-release-binaries.yaml")).unwrap();
+    assert!(workflow.contains("/release/foo${{ matrix.exe-suffix }} foo.${{ github.ref_name }}"));
+    assert!(workflow.contains("files: foo.${{ github.ref_name }}"));
+    assert!(!workflow.contains("@acme"));
+}
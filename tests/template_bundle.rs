@@ -0,0 +1,62 @@
+mod common;
+
+use std::{
+    fs::{create_dir_all, metadata, read_to_string, write},
+    os::unix::fs::PermissionsExt,
+    process::Output,
+};
+
+use common::TempDir;
+
+const REPO_JSON: &str = "{\n  \"other\": true\n}\n";
+
+fn repo_post_version_hook(dir: &TempDir, args: &[&str]) -> Output {
+    dir.repo(dir)
+        .args([
+            "boilerplate",
+            "post-version-hook",
+            "add",
+            "--followup",
+            "none",
+        ])
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_template_bundle_is_all_or_nothing() {
+    let dir = TempDir::new("template-bundle");
+    create_dir_all(dir.join(".config")).unwrap();
+    create_dir_all(dir.join("script")).unwrap();
+    write(dir.join(".config/repo.json"), REPO_JSON).unwrap();
+    write(dir.join("script/postVersion.ts"), "// existing\n").unwrap();
+
+    // The script already exists, so nothing (including the config patch) is written.
+    let output = repo_post_version_hook(&dir, &[]);
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(
+        read_to_string(dir.join(".config/repo.json")).unwrap(),
+        REPO_JSON
+    );
+    assert_eq!(
+        read_to_string(dir.join("script/postVersion.ts")).unwrap(),
+        "// existing\n"
+    );
+
+    assert!(repo_post_version_hook(&dir, &["--overwrite"])
+        .status
+        .success());
+    assert!(read_to_string(dir.join("script/postVersion.ts"))
+        .unwrap()
+        .starts_with("#!/usr/bin/env"));
+    let mode = metadata(dir.join("script/postVersion.ts"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o755);
+    assert_eq!(
+        read_to_string(dir.join(".config/repo.json")).unwrap(),
+        "{\n  \"other\": true,\n  \"scripts\": {\n    \"postVersion\": [\"./script/postVersion.ts\"]\n  }\n}\n"
+    );
+}
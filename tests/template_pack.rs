@@ -0,0 +1,61 @@
+mod common;
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    process::Output,
+};
+
+use common::{git, TempDir};
+
+fn repo_boilerplate(dir: &TempDir, args: &[&str]) -> Output {
+    dir.repo(dir.join("project"))
+        .env("XDG_CACHE_HOME", dir.join("cache"))
+        .arg("boilerplate")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_template_pack_is_pinned() {
+    let dir = TempDir::new("template-pack");
+    let pack = dir.join("acme-templates");
+    create_dir_all(pack.join("templates")).unwrap();
+    create_dir_all(dir.join("project")).unwrap();
+    git(&dir.join("project"), &["init", "--quiet"]);
+    git(&pack, &["init", "--quiet"]);
+    write(pack.join("templates/.editorconfig"), "root = true\n").unwrap();
+    git(&pack, &["add", "--all"]);
+    git(&pack, &["commit", "--quiet", "-m", "v1"]);
+
+    // Local paths are stored relative to the repo root.
+    assert!(
+        repo_boilerplate(&dir, &["pack", "add", pack.to_str().unwrap()])
+            .status
+            .success()
+    );
+    assert!(read_to_string(dir.join("project/.config/repo.json"))
+        .unwrap()
+        .contains("\"source\": \"../acme-templates\""));
+    assert!(repo_boilerplate(
+        &dir,
+        &["acme-templates/editorconfig", "add", "--followup", "none"]
+    )
+    .status
+    .success());
+    assert_eq!(
+        read_to_string(dir.join("project/.editorconfig")).unwrap(),
+        "root = true\n"
+    );
+
+    // New commits in the pack are not used until it is added again.
+    write(pack.join("templates/.editorconfig"), "root = false\n").unwrap();
+    git(&pack, &["commit", "--quiet", "--all", "-m", "v2"]);
+    assert!(repo_boilerplate(&dir, &["status"]).status.success());
+    assert!(
+        repo_boilerplate(&dir, &["pack", "add", "../acme-templates"])
+            .status
+            .success()
+    );
+    assert_eq!(repo_boilerplate(&dir, &["status"]).status.code(), Some(8));
+}